@@ -1,156 +1,233 @@
-use core::num;
-use std::{collections::HashMap, fs, ops::Add, path::Path};
-
-fn extract_number(mut text: String, convert_spelled_numbers: bool) -> i32 {
-    if convert_spelled_numbers {
-        return replace_spelled_numbers(text);
-    }
-    let mut numbers: [Option<String>; 2] = [None, None];
-    for n in text.chars() {
-        if n.is_numeric() {
-            if numbers[0].is_none() {
-                numbers[0] = Some(n.to_string());
-            } else {
-                numbers[1] = Some(n.to_string());
-            }
+use std::{cmp::Reverse, fmt};
+
+use crate::shared::{
+    answer::Answer,
+    file_parser::{get_input, get_rows},
+    registry::DayPart,
+};
+
+const ENGLISH_DIGITS: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+#[derive(Debug, PartialEq)]
+struct NoDigitError {
+    line: String,
+}
+
+impl fmt::Display for NoDigitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No digit in line {:?}", self.line)
+    }
+}
+
+// The words that count as a digit. Words are looked up by their first byte,
+// so finding the first digit is one pass from the start of the line and the
+// last digit one pass from the end. Matches may overlap, in "eightwo" the
+// first digit is 8 and the last one 2.
+struct DigitVocabulary {
+    words: Vec<(Vec<u8>, u32)>,
+    by_first_byte: Vec<Vec<usize>>,
+}
+
+impl DigitVocabulary {
+    fn new<'a>(words: impl IntoIterator<Item = (&'a str, u32)>) -> Self {
+        let words: Vec<(Vec<u8>, u32)> = words
+            .into_iter()
+            .map(|(word, digit)| {
+                assert!(!word.is_empty(), "Empty word for digit {}", digit);
+                (word.as_bytes().to_vec(), digit)
+            })
+            .collect();
+        let mut by_first_byte = vec![Vec::new(); 256];
+        for (index, (word, _)) in words.iter().enumerate() {
+            by_first_byte[word[0] as usize].push(index);
+        }
+        // longest words first so a word isn't shadowed by one of its prefixes
+        for indexes in by_first_byte.iter_mut() {
+            indexes.sort_by_key(|index| Reverse(words[*index].0.len()));
+        }
+        Self {
+            words,
+            by_first_byte,
         }
     }
-    if numbers[0].is_none() {
-        return 0;
+
+    fn digits() -> Self {
+        let digits: Vec<String> = (0..10).map(|digit| digit.to_string()).collect();
+        Self::new(digits.iter().map(String::as_str).zip(0..))
     }
-    if numbers[1].is_none() {
-        numbers[1] = numbers[0].clone();
+
+    // Digits and the spelled out digits of `names`, zero first
+    fn spelled(names: &[&str]) -> Self {
+        let digits: Vec<String> = (0..10).map(|digit| digit.to_string()).collect();
+        let digits = digits.iter().map(String::as_str).zip(0..);
+        Self::new(digits.chain(names.iter().copied().zip(0..)))
+    }
+
+    fn english() -> Self {
+        Self::spelled(&ENGLISH_DIGITS)
     }
-    numbers
-        .iter()
-        .map(|option| match option {
-            Some(number) => number.to_owned(),
-            None => String::from(""),
-        })
-        .collect::<String>()
-        .parse()
-        .expect("Should not happen")
-}
 
-fn replace_spelled_numbers(input: String) -> i32 {
-    if input.is_empty() {
-        return 0;
+    fn match_at(&self, line: &[u8], index: usize) -> Option<u32> {
+        self.by_first_byte[line[index] as usize]
+            .iter()
+            .map(|word| &self.words[*word])
+            .find(|(word, _)| line[index..].starts_with(word))
+            .map(|(_, digit)| *digit)
     }
-    let mut numbers = Vec::<(usize, String)>::new();
 
-    let mut concat_matching_indice = |str_to_match: &str, value_to_use: &str| {
-        for (index, _) in input
-            .match_indices(str_to_match)
-            .collect::<Vec<(usize, &str)>>()
-        {
-            numbers.push((index, value_to_use.to_owned()));
+    fn first(&self, line: &str) -> Option<u32> {
+        let line = line.as_bytes();
+        (0..line.len()).find_map(|index| self.match_at(line, index))
+    }
+
+    fn last(&self, line: &str) -> Option<u32> {
+        let line = line.as_bytes();
+        (0..line.len())
+            .rev()
+            .find_map(|index| self.match_at(line, index))
+    }
+
+    fn calibration_value(&self, line: &str) -> Result<u32, NoDigitError> {
+        match (self.first(line), self.last(line)) {
+            (Some(first), Some(last)) => Ok(first * 10 + last),
+            _ => Err(NoDigitError {
+                line: line.to_owned(),
+            }),
         }
-    };
-    let mut concat_matching_indices = |spelled_number: &str, numeric_number: &str| {
-        concat_matching_indice(spelled_number, numeric_number);
-        concat_matching_indice(numeric_number, numeric_number);
-    };
-    concat_matching_indices("zero", "0");
-    concat_matching_indices("one", "1");
-    concat_matching_indices("two", "2");
-    concat_matching_indices("three", "3");
-    concat_matching_indices("four", "4");
-    concat_matching_indices("five", "5");
-    concat_matching_indices("six", "6");
-    concat_matching_indices("seven", "7");
-    concat_matching_indices("eight", "8");
-    concat_matching_indices("nine", "9");
-    numbers.sort_by(|(indexa, _), (indexb, _)| indexa.cmp(indexb));
-    let mut output = String::from("");
-    match numbers.first() {
-        Some((_, value)) => output.push_str(value),
-        None => todo!(),
-    };
-    match numbers.last() {
-        Some((_, value)) => output.push_str(value),
-        None => todo!(),
-    };
-    match output.parse() {
-        Ok(result) => result,
-        Err(_) => todo!(),
     }
 }
 
-fn extract_total(input: Vec<String>, convert_spelled_numbers: bool) -> i32 {
-    let mut total: i32 = 0;
-    for row in input {
-        total += extract_number(row, convert_spelled_numbers);
-    }
-    total
+fn extract_total(input: Vec<String>, convert_spelled_numbers: bool) -> Result<u32, NoDigitError> {
+    let vocabulary = if convert_spelled_numbers {
+        DigitVocabulary::english()
+    } else {
+        DigitVocabulary::digits()
+    };
+    input
+        .iter()
+        .map(|row| vocabulary.calibration_value(row))
+        .sum()
+}
+
+pub fn parts() -> Vec<DayPart> {
+    let solve = |convert_spelled_numbers| {
+        let input = get_input(file!(), "input.txt");
+        Answer::from(extract_total(get_rows(input), convert_spelled_numbers).unwrap())
+    };
+    vec![
+        DayPart::new(2023, 1, 1, move || solve(false)).expecting(55029),
+        DayPart::new(2023, 1, 2, move || solve(true)).expecting(55686),
+    ]
 }
 
 #[cfg(test)]
 mod tests {
     use crate::shared::file_parser::{get_input, get_rows};
-    use crate::_2023::day1::day_one::{extract_number, extract_total};
+    use crate::shared::readme::check_readme_examples;
 
-    use super::replace_spelled_numbers;
+    use super::*;
 
     #[test]
     fn example_two_digit_number() {
-        let test_cases: Vec<(String, i32)> = vec![
-            (String::from("1abc2"), 12),
-            (String::from("pqr3stu8vwx"), 38),
-            (String::from("a1b2c3d4e5f"), 15),
-            (String::from("treb7uchet"), 77),
+        let test_cases = [
+            ("1abc2", 12),
+            ("pqr3stu8vwx", 38),
+            ("a1b2c3d4e5f", 15),
+            ("treb7uchet", 77),
         ];
-
+        let vocabulary = DigitVocabulary::digits();
         for (input, expected) in test_cases {
-            assert_eq!(extract_number(input, false), expected);
+            assert_eq!(Ok(expected), vocabulary.calibration_value(input));
         }
     }
 
     #[test]
     fn first_example() {
         let input = get_input(file!(), "example.txt");
-        assert_eq!(extract_total(get_rows(input), false), 142);
+        assert_eq!(extract_total(get_rows(input), false), Ok(142));
     }
 
     #[test]
     fn first_answer() {
         let input = get_input(file!(), "input.txt");
-        assert_eq!(extract_total(get_rows(input), false), 55029);
+        assert_eq!(extract_total(get_rows(input), false), Ok(55029));
     }
 
     #[test]
     fn test_number_replacement() {
-        let test_cases: Vec<(String, i32)> = vec![
-            (String::from("onetwothreefourfivesixseveneightnine"), 19),
-            (String::from("oneight"), 18),
-            (String::from("one2eighthree"), 13),
+        let test_cases = [
+            ("onetwothreefourfivesixseveneightnine", 19),
+            ("oneight", 18),
+            ("one2eighthree", 13),
+            ("two1nine", 29),
+            ("eightwothree", 83),
+            ("abcone2threexyz", 13),
+            ("xtwone3four", 24),
+            ("4nineeightseven2", 42),
+            ("zoneight234", 14),
+            ("7pqrstsixteen", 76),
+            ("eightwo", 82),
+            ("sevenine", 79),
+            ("nine", 99),
         ];
+        let vocabulary = DigitVocabulary::english();
         for (input, expected) in test_cases {
-            assert_eq!(replace_spelled_numbers(input), expected);
+            assert_eq!(
+                Ok(expected),
+                vocabulary.calibration_value(input),
+                "{}",
+                input
+            );
         }
-        let test_cases: Vec<(String, i32)> = vec![
-            (String::from("two1nine"), 29),
-            (String::from("eightwothree"), 83),
-            (String::from("abcone2threexyz"), 13),
-            (String::from("xtwone3four"), 24),
-            (String::from("4nineeightseven2"), 42),
-            (String::from("zoneight234"), 14),
-            (String::from("7pqrstsixteen"), 76),
-        ];
+    }
 
-        for (input, expected) in test_cases {
-            assert_eq!(extract_number(input, true), expected);
-        }
+    #[test]
+    fn digit_less_lines_are_errors() {
+        assert_eq!(
+            Err(NoDigitError {
+                line: String::from("abc")
+            }),
+            DigitVocabulary::english().calibration_value("abc")
+        );
+        assert!(DigitVocabulary::digits().calibration_value("one").is_err());
+        assert!(extract_total(vec![String::from("1"), String::new()], false).is_err());
+    }
+
+    #[test]
+    fn other_vocabularies() {
+        let german = DigitVocabulary::spelled(&[
+            "null", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+        ]);
+        assert_eq!(Ok(51), german.calibration_value("xfünfzehneins"));
+        assert_eq!(Ok(71), german.calibration_value("siebenzweins"));
+        let roman = DigitVocabulary::new([("i", 1), ("ii", 2), ("v", 5)]);
+        assert_eq!(Ok(15), roman.calibration_value("xivx"));
+        assert_eq!(Some(2), roman.first("ii"));
+        // the last word is the "i" starting at the second character
+        assert_eq!(Ok(21), roman.calibration_value("ii"));
     }
 
     #[test]
     fn second_example() {
         let input = get_input(file!(), "example2.txt");
-        assert_eq!(extract_total(get_rows(input), true), 281);
+        assert_eq!(extract_total(get_rows(input), true), Ok(281));
     }
 
     #[test]
     fn second_example_solution() {
         let input = get_input(file!(), "input.txt");
-        assert_eq!(extract_total(get_rows(input), true), 55686);
+        assert_eq!(extract_total(get_rows(input), true), Ok(55686));
+    }
+
+    #[test]
+    fn readme_examples() {
+        check_readme_examples(
+            file!(),
+            &["example.txt", "example2.txt"],
+            "input.txt",
+            |part, input| extract_total(get_rows(input), part == 2).unwrap(),
+        );
     }
 }
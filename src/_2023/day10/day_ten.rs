@@ -1,6 +1,17 @@
 use std::{
-    collections::HashMap,
-    ops::{AddAssign, SubAssign},
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::{
+    shared::{
+        answer::Answer,
+        file_parser::{get_input, get_rows},
+        grid_renderer::{Arrow, GridRenderer},
+        image_export::{DayImage, GridImage, Rgb},
+        registry::DayPart,
+    },
+    trace,
 };
 
 type Coordinate = (usize, usize);
@@ -82,19 +93,215 @@ impl Pipe {
     }
 }
 
+#[derive(Debug, PartialEq)]
+enum MazeError {
+    NoStart,
+    NoLoop,
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MazeError::NoStart => write!(f, "the maze has no start pipe"),
+            MazeError::NoLoop => write!(f, "no loop runs through the start pipe"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tile {
+    Loop,
+    Inside,
+    Outside,
+}
+
+// The pipe that connects a tile to two of its neighbours
+fn shape_between(tile: Coordinate, first: Coordinate, second: Coordinate) -> Option<char> {
+    let sides = [Arrow::between(tile, first)?, Arrow::between(tile, second)?];
+    let connects = |side: Arrow| sides.contains(&side);
+    match (
+        connects(Arrow::Up),
+        connects(Arrow::Right),
+        connects(Arrow::Down),
+        connects(Arrow::Left),
+    ) {
+        (true, false, true, false) => Some('|'),
+        (false, true, false, true) => Some('-'),
+        (true, true, false, false) => Some('L'),
+        (true, false, false, true) => Some('J'),
+        (false, false, true, true) => Some('7'),
+        (false, true, true, false) => Some('F'),
+        _ => None,
+    }
+}
+
+fn box_character(pipe: char) -> char {
+    match pipe {
+        '|' => '│',
+        '-' => '─',
+        'L' => '└',
+        'J' => '┘',
+        '7' => '┐',
+        'F' => '┌',
+        other => other,
+    }
+}
+
+// The loop through the start pipe and on which side of it every other tile is
+struct LoopAnalysis {
+    start: Coordinate,
+    // the pipe hidden under the start
+    start_shape: char,
+    // from the start round to the pipe before it
+    pipe_loop: Vec<Coordinate>,
+    tiles: HashMap<Coordinate, Tile>,
+}
+
+impl LoopAnalysis {
+    fn tiles_of(&self, kind: Tile) -> HashSet<Coordinate> {
+        self.tiles
+            .iter()
+            .filter(|(_, tile)| **tile == kind)
+            .map(|(coordinate, _)| *coordinate)
+            .collect()
+    }
+
+    fn loop_tiles(&self) -> HashSet<Coordinate> {
+        self.tiles_of(Tile::Loop)
+    }
+
+    fn enclosed_tiles(&self) -> HashSet<Coordinate> {
+        self.tiles_of(Tile::Inside)
+    }
+
+    fn farthest_distance(&self) -> usize {
+        self.pipe_loop.len() / 2
+    }
+}
+
 struct PipeMazeWrapper {
     value: HashMap<Coordinate, Pipe>,
 }
 
 impl PipeMazeWrapper {
-    fn get_start(&self) -> &Pipe {
-        *self
-            .value
-            .values()
-            .filter(|v| v.character.eq(&'S'))
-            .collect::<Vec<&Pipe>>()
-            .first()
-            .unwrap()
+    // Follows the pipes on from `first_pipe`, the path when they lead back to
+    // the start pipe
+    fn walk_back_to_start(&self, start_pipe: &Pipe, first_pipe: &Pipe) -> Option<Vec<Coordinate>> {
+        let mut pipe_loop = vec![start_pipe.coordinate];
+        let mut previous_pipe = start_pipe;
+        let mut current_pipe = first_pipe;
+        while current_pipe.ne(start_pipe) {
+            pipe_loop.push(current_pipe.coordinate);
+            let next_pipe = current_pipe.find_next(previous_pipe, self)?;
+            previous_pipe = current_pipe;
+            current_pipe = next_pipe;
+        }
+        Some(pipe_loop)
+    }
+
+    // The first neighbour of the start that leads back round to it decides the
+    // loop, the pipe it arrives from decides the shape under the start. Rows
+    // are then scanned flipping between outside and inside each time a loop
+    // pipe that connects upwards is crossed.
+    fn analyse_loop(&self) -> Result<LoopAnalysis, MazeError> {
+        let start_pipe = self.get_start().ok_or(MazeError::NoStart)?;
+        let pipe_loop = start_pipe
+            .links
+            .iter()
+            .filter_map(|c| self.value.get(c))
+            .filter(|first_pipe| first_pipe.links.contains(&start_pipe.coordinate))
+            .find_map(|first_pipe| self.walk_back_to_start(start_pipe, first_pipe))
+            .ok_or(MazeError::NoLoop)?;
+        let start_shape = shape_between(
+            start_pipe.coordinate,
+            pipe_loop[1],
+            pipe_loop[pipe_loop.len() - 1],
+        )
+        .ok_or(MazeError::NoLoop)?;
+
+        let loop_coordinates: HashSet<Coordinate> = pipe_loop.iter().copied().collect();
+        let connects_up = |pipe: &Pipe| match pipe.character {
+            'S' => matches!(start_shape, '|' | 'L' | 'J'),
+            character => matches!(character, '|' | 'L' | 'J'),
+        };
+        let (max_x, max_y) = self.max_coordinate();
+        let mut tiles = HashMap::new();
+        for y in 0..max_y + 1 {
+            let mut inside = false;
+            for x in 0..max_x + 1 {
+                let tile = if loop_coordinates.contains(&(x, y)) {
+                    if connects_up(&self.value[&(x, y)]) {
+                        inside = !inside;
+                    }
+                    Tile::Loop
+                } else if inside {
+                    Tile::Inside
+                } else {
+                    Tile::Outside
+                };
+                tiles.insert((x, y), tile);
+            }
+        }
+        Ok(LoopAnalysis {
+            start: start_pipe.coordinate,
+            start_shape,
+            pipe_loop,
+            tiles,
+        })
+    }
+
+    fn max_coordinate(&self) -> Coordinate {
+        let max_x = self.value.keys().map(|c| c.0).max().unwrap_or(0);
+        let max_y = self.value.keys().map(|c| c.1).max().unwrap_or(0);
+        (max_x, max_y)
+    }
+
+    // The loop drawn with box characters and the start as @, enclosed tiles as
+    // I and all others as O. In colour the enclosed tiles are highlighted.
+    fn render_classification<'a>(
+        &'a self,
+        analysis: &'a LoopAnalysis,
+    ) -> GridRenderer<impl Fn(usize, usize) -> char + 'a> {
+        let (max_x, max_y) = self.max_coordinate();
+        GridRenderer::new(max_x + 1, max_y + 1, move |x, y| {
+            match analysis.tiles.get(&(x, y)) {
+                Some(Tile::Loop) => box_character(self.value[&(x, y)].character),
+                Some(Tile::Inside) => 'I',
+                _ => 'O',
+            }
+        })
+        .with_visited(analysis.enclosed_tiles(), None)
+        .with_actor(analysis.start, None)
+    }
+
+    // The loop in blue with the tiles it encloses shaded in yellow
+    fn loop_image(&self) -> GridImage<impl Fn(usize, usize) -> Rgb + '_> {
+        let analysis = self.analyse_loop().ok();
+        let (loop_coordinates, enclosed) = analysis
+            .as_ref()
+            .map(|analysis| (analysis.loop_tiles(), analysis.enclosed_tiles()))
+            .unwrap_or_default();
+        let mut path = analysis
+            .map(|analysis| analysis.pipe_loop)
+            .unwrap_or_default();
+        if let Some(start) = path.first().copied() {
+            path.push(start);
+        }
+        let (max_x, max_y) = self.max_coordinate();
+        GridImage::new(max_x + 1, max_y + 1, move |x, y| {
+            if loop_coordinates.contains(&(x, y)) {
+                Rgb::BLUE
+            } else if enclosed.contains(&(x, y)) {
+                Rgb::YELLOW
+            } else {
+                Rgb::WHITE
+            }
+        })
+        .with_path(&path, Rgb::BLACK)
+    }
+
+    fn get_start(&self) -> Option<&Pipe> {
+        self.value.values().find(|v| v.character.eq(&'S'))
     }
 }
 
@@ -120,7 +327,7 @@ fn pipe_maze_from_string(rows: Vec<String>) -> PipeMazeMap {
             if let Some(ch) = c.chars().next() {
                 pipe_maze.insert((x, y), ch);
             } else {
-                println!("{}, {}", x, y);
+                trace!(Warn, "No pipe character at {}, {}", x, y);
             }
         }
     }
@@ -128,102 +335,49 @@ fn pipe_maze_from_string(rows: Vec<String>) -> PipeMazeMap {
 }
 
 fn solution_1(pipe_maze: PipeMazeWrapper) -> usize {
-    let mut pipe_count_collection: Vec<usize> = Vec::new();
-    let start_pipe = pipe_maze.get_start();
-
-    for first_pipe_coordinate in &start_pipe.links {
-        let mut has_next = true;
-        if let Some(first_pipe) = pipe_maze.value.get(first_pipe_coordinate) {
-            if !first_pipe.links.contains(&start_pipe.coordinate) {
-                continue;
-            }
-            // starting with 1 because we skipped the first link
-            let mut nr_of_pipes = 1;
-            let mut previous_pipe = start_pipe;
-            let mut current_pipe = first_pipe;
-            while has_next && current_pipe.ne(&start_pipe) {
-                match current_pipe.find_next(previous_pipe, &pipe_maze) {
-                    Some(next_pipe) => {
-                        nr_of_pipes.add_assign(1);
-                        previous_pipe = current_pipe;
-                        current_pipe = next_pipe;
-                    }
-                    None => has_next = false,
-                }
-            }
-            if has_next {
-                pipe_count_collection.push(nr_of_pipes);
-            }
-        }
+    match pipe_maze.analyse_loop() {
+        Ok(analysis) => analysis.farthest_distance(),
+        Err(error) => panic!("{}", error),
     }
-
-    pipe_count_collection.into_iter().min().unwrap() / 2
 }
 
+// With the trace level for this day the classified maze is drawn as well
 fn solution_2(pipe_maze: PipeMazeWrapper) -> usize {
-    let start_pipe = pipe_maze.get_start();
-    let mut pipe_loops = Vec::new();
-    let mut already_done_in_reverse = Vec::new();
-
-    for first_pipe_coordinate in &start_pipe.links {
-        let mut connected_pipes: Vec<&Pipe> = Vec::new();
-        let mut has_next = true;
-        if let Some(first_pipe) = pipe_maze.value.get(first_pipe_coordinate) {
-            if !first_pipe.links.contains(&start_pipe.coordinate) {
-                continue;
-            }
-            // don't iterate over a loop that was already closed before in the
-            // other direction
-            if already_done_in_reverse.contains(&first_pipe) {
-                continue;
-            }
-            connected_pipes.push(first_pipe);
-            // starting with 1 because we skipped the first link
-            let mut previous_pipe = start_pipe;
-            let mut current_pipe = first_pipe;
-            while has_next && current_pipe.ne(&start_pipe) {
-                match current_pipe.find_next(previous_pipe, &pipe_maze) {
-                    Some(next_pipe) => {
-                        connected_pipes.push(next_pipe);
-                        previous_pipe = current_pipe;
-                        current_pipe = next_pipe;
-                    }
-                    None => {
-                        has_next = false;
-                        connected_pipes = Vec::new();
-                    }
-                }
-            }
-            if has_next {
-                already_done_in_reverse.push(previous_pipe);
-                pipe_loops.push(connected_pipes);
-            }
+    match pipe_maze.analyse_loop() {
+        Ok(analysis) => {
+            trace!(
+                Debug,
+                "the pipe under the start at {:?} is {}, {} tiles are on the loop",
+                analysis.start,
+                analysis.start_shape,
+                analysis.loop_tiles().len()
+            );
+            trace!(Trace, "\n{}", pipe_maze.render_classification(&analysis));
+            analysis.enclosed_tiles().len()
         }
+        Err(error) => panic!("{}", error),
     }
-    pipe_loops
-        .into_iter()
-        .map(|v| calculate_area(v))
-        .min()
-        .unwrap()
 }
 
-fn calculate_area(pipe_loop: Vec<&Pipe>) -> usize {
-    // using shoelace formula
-    let coordinates = pipe_loop
-        .iter()
-        .map(|p| p.coordinate)
-        .collect::<Vec<Coordinate>>();
-    let n = coordinates.len();
-    let mut area = 0isize;
-
-    for i in 0..n {
-        let j = (i + 1) % n;
-        area.add_assign((coordinates[i].0 * coordinates[j].1) as isize);
-        area.sub_assign((coordinates[j].0 * coordinates[i].1) as isize);
-    }
+pub fn parts() -> Vec<DayPart> {
+    let pipe_maze = || {
+        let rows = get_rows(get_input(file!(), "input1.txt"));
+        PipeMazeWrapper::from(pipe_maze_from_string(rows))
+    };
+    vec![
+        DayPart::new(2023, 10, 1, move || Answer::from(solution_1(pipe_maze()))).expecting(6931),
+        DayPart::new(2023, 10, 2, move || Answer::from(solution_2(pipe_maze()))).expecting(357),
+    ]
+}
 
-    // combining shoelace formula with pick's formula
-    (area.abs() / 2) as usize - (n / 2) + 1
+// The loop of the real input
+pub fn image() -> DayImage {
+    DayImage::new(2023, 10, |path, config| {
+        let rows = get_rows(get_input(file!(), "input1.txt"));
+        let pipe_maze = PipeMazeWrapper::from(pipe_maze_from_string(rows));
+        let image = pipe_maze.loop_image();
+        image.save(path, config)
+    })
 }
 
 #[cfg(test)]
@@ -285,6 +439,98 @@ mod tests {
         assert_eq!(expected_result, solution, "{}", file_name);
     }
 
+    #[test]
+    fn example_2_enclosed_tiles_test() {
+        for (file_name, expected_result) in [
+            ("example2.txt", 10),
+            ("example3.txt", 13),
+            ("example4.txt", 37),
+            ("example5.txt", 13),
+        ] {
+            let input = get_input(file!(), file_name);
+            let pipe_maze = PipeMazeWrapper::from(pipe_maze_from_string(get_rows(input)));
+            let analysis = pipe_maze.analyse_loop().unwrap();
+            let enclosed = analysis.enclosed_tiles();
+            assert_eq!(expected_result, enclosed.len(), "{}", file_name);
+            let outside = analysis.tiles_of(Tile::Outside);
+            assert!(enclosed.is_disjoint(&analysis.loop_tiles()));
+            assert!(outside.is_disjoint(&analysis.loop_tiles()));
+            assert_eq!(
+                analysis.tiles.len(),
+                enclosed.len() + outside.len() + analysis.pipe_loop.len(),
+                "{}",
+                file_name
+            );
+        }
+    }
+
+    fn maze(file_name: &str) -> PipeMazeWrapper {
+        PipeMazeWrapper::from(pipe_maze_from_string(get_rows(get_input(
+            file!(),
+            file_name,
+        ))))
+    }
+
+    #[test]
+    fn start_shape() {
+        for (file_name, expected_shape) in [
+            ("example1.txt", 'F'),
+            ("example3.txt", '|'),
+            ("example4.txt", 'F'),
+            ("example5.txt", 'F'),
+        ] {
+            let analysis = maze(file_name).analyse_loop().unwrap();
+            assert_eq!(expected_shape, analysis.start_shape, "{}", file_name);
+        }
+    }
+
+    #[test]
+    fn dead_end_next_to_the_start() {
+        let rows = vec![String::from("-S7"), String::from(".LJ")];
+        let pipe_maze = PipeMazeWrapper::from(pipe_maze_from_string(rows));
+        let analysis = pipe_maze.analyse_loop().unwrap();
+        assert_eq!('F', analysis.start_shape);
+        assert_eq!(vec![(1, 0), (2, 0), (2, 1), (1, 1)], analysis.pipe_loop);
+        assert_eq!(Some(&Tile::Outside), analysis.tiles.get(&(0, 0)));
+        assert_eq!(
+            "O@┐\nO└┘",
+            pipe_maze.render_classification(&analysis).to_string()
+        );
+    }
+
+    #[test]
+    fn maze_errors() {
+        let without_start = PipeMazeWrapper::from(pipe_maze_from_string(vec![String::from("F7")]));
+        assert_eq!(
+            Err(MazeError::NoStart),
+            without_start.analyse_loop().map(|a| a.start_shape)
+        );
+        let without_loop = PipeMazeWrapper::from(pipe_maze_from_string(vec![String::from("S-7")]));
+        assert_eq!(
+            Err(MazeError::NoLoop),
+            without_loop.analyse_loop().map(|a| a.start_shape)
+        );
+    }
+
+    #[test]
+    fn example_2_render_classification() {
+        let pipe_maze = maze("example3.txt");
+        let analysis = pipe_maze.analyse_loop().unwrap();
+        let expected = [
+            "OOOOOOOOO",
+            "O┌──────┐",
+            "O@IIIIII│",
+            "O│I┌─┐II│",
+            "O│I│O│II│",
+            "O└─┘O│I┌┘",
+            "OOOOO└─┘O",
+        ];
+        assert_eq!(
+            expected.join("\n"),
+            pipe_maze.render_classification(&analysis).to_string()
+        );
+    }
+
     #[test]
     fn solution_2_test() {
         let input = get_input(file!(), "input1.txt");
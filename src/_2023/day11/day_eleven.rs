@@ -1,5 +1,11 @@
 use std::ops::AddAssign;
 
+use crate::shared::{
+    answer::Answer,
+    file_parser::{get_input, get_rows},
+    registry::DayPart,
+};
+
 #[derive(Hash, PartialEq, Eq)]
 struct Galaxy {
     id: usize,
@@ -118,6 +124,18 @@ impl MilkyWay {
     }
 }
 
+pub fn parts() -> Vec<DayPart> {
+    let total_distance = |expansion| {
+        let mut milky_way = MilkyWay::from(get_rows(get_input(file!(), "input1.txt")));
+        milky_way.expand_milky_way(expansion);
+        Answer::from(milky_way.calculate_total_distance().0)
+    };
+    vec![
+        DayPart::new(2023, 11, 1, move || total_distance(2)).expecting(9639160),
+        DayPart::new(2023, 11, 2, move || total_distance(1_000_000)).expecting(752936133304u64),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use crate::shared::{
@@ -1,5 +1,11 @@
 use std::vec;
 
+use crate::shared::{
+    answer::Answer,
+    file_parser::{get_input, get_rows},
+    registry::DayPart,
+};
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 enum Spring {
     Unknown,
@@ -127,12 +133,95 @@ fn fast_solution(spring_rows: Vec<SpringRow>) -> usize {
         .sum()
 }
 
+pub fn parts() -> Vec<DayPart> {
+    let spring_rows = || {
+        get_rows(get_input(file!(), "input1.txt"))
+            .into_iter()
+            .filter(|row| !row.is_empty())
+            .map(|row| SpringRow::from(row.as_str()))
+    };
+    vec![
+        DayPart::new(2023, 12, 1, move || {
+            Answer::from(fast_solution(spring_rows().collect()))
+        })
+        .expecting(7407),
+        DayPart::new(2023, 12, 2, move || {
+            Answer::from(fast_solution(
+                spring_rows().map(|row| row.to_s2()).collect(),
+            ))
+        })
+        .expecting(30568243604962u64),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::shared::file_parser::{get_input, get_rows};
+    use crate::shared::{
+        differential::Differential,
+        file_parser::{get_input, get_rows},
+        method_duration::log_method_duration,
+        random::{InputGenerator, Rng},
+    };
 
     use super::*;
 
+    // Rows are drawn as working and broken springs first and the checksum is read
+    // from that, so every generated row has at least one arrangement
+    struct SpringRowGenerator {
+        rows: usize,
+        max_length: usize,
+        // chance out of 100 for a spring to be hidden behind a '?'
+        unknown_percentage: usize,
+    }
+
+    impl Default for SpringRowGenerator {
+        fn default() -> Self {
+            Self {
+                rows: 1000,
+                max_length: 20,
+                unknown_percentage: 50,
+            }
+        }
+    }
+
+    impl InputGenerator for SpringRowGenerator {
+        fn generate(&self, rng: &mut Rng) -> String {
+            let mut rows = Vec::new();
+            for _ in 0..self.rows {
+                let length = rng.range(1..self.max_length + 1);
+                let mut springs: Vec<bool> = (0..length).map(|_| rng.chance(1, 2)).collect();
+                // the checksum can't be empty
+                let first_broken = rng.range(0..length);
+                springs[first_broken] = true;
+
+                let mut checksum = Vec::new();
+                let mut group = 0;
+                for broken in springs.iter().chain([false].iter()) {
+                    if *broken {
+                        group += 1;
+                    } else if group > 0 {
+                        checksum.push(group.to_string());
+                        group = 0;
+                    }
+                }
+                let springs: String = springs
+                    .iter()
+                    .map(|broken| {
+                        if rng.chance(self.unknown_percentage, 100) {
+                            '?'
+                        } else if *broken {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect();
+                rows.push(format!("{} {}", springs, checksum.join(",")));
+            }
+            rows.join("\n")
+        }
+    }
+
     #[test]
     fn test_spring_row_parser() {
         let row = "???.### 1,1,3";
@@ -288,4 +377,73 @@ mod tests {
 
         assert_eq!(30568243604962, fast_solution(spring_rows));
     }
+
+    // Tries every way to fill in the unknown springs
+    fn brute_force_arrangements(row: &SpringRow) -> usize {
+        let unknowns: Vec<usize> = (0..row.springs.len())
+            .filter(|index| row.springs[*index] == Spring::Unknown)
+            .collect();
+        (0..1usize << unknowns.len())
+            .filter(|mask| {
+                let mut groups = Vec::new();
+                let mut group = 0;
+                for (index, spring) in row.springs.iter().enumerate() {
+                    let broken = match unknowns.iter().position(|u| *u == index) {
+                        Some(bit) => mask >> bit & 1 == 1,
+                        None => *spring == Spring::Broken,
+                    };
+                    if broken {
+                        group += 1;
+                    } else if group > 0 {
+                        groups.push(group);
+                        group = 0;
+                    }
+                }
+                if group > 0 {
+                    groups.push(group);
+                }
+                groups == row.checksum
+            })
+            .count()
+    }
+
+    #[test]
+    fn generated_rows_have_arrangements() {
+        let generator = SpringRowGenerator {
+            rows: 300,
+            max_length: 12,
+            ..Default::default()
+        };
+        let input = generator.generate_with_seed(12);
+        assert_eq!(input, generator.generate_with_seed(12));
+        let rows: Vec<SpringRow> = input.lines().map(SpringRow::from).collect();
+        assert_eq!(300, rows.len());
+        let differential = Differential::new(
+            "count_possible_arangements",
+            |row: &SpringRow| row.clone().count_possible_arangements(),
+            "brute_force_arrangements",
+            brute_force_arrangements,
+        );
+        assert!(rows.iter().all(|row| brute_force_arrangements(row) > 0));
+        if let Err(disagreement) = differential.check(rows) {
+            panic!("{}", disagreement);
+        }
+    }
+
+    #[test]
+    #[ignore = "stress test, run with --ignored --nocapture"]
+    fn stress_generated_rows() {
+        let generator = SpringRowGenerator {
+            rows: 20_000,
+            max_length: 40,
+            ..Default::default()
+        };
+        let input = generator.generate_with_seed(12);
+        let spring_rows = input
+            .lines()
+            .map(|r| SpringRow::from(r).to_s2())
+            .collect::<Vec<SpringRow>>();
+        let result = log_method_duration(|| fast_solution(spring_rows));
+        assert!(result >= 20_000);
+    }
 }
@@ -2,6 +2,8 @@ use std::ops::{AddAssign, SubAssign};
 
 use itertools::Itertools;
 
+use crate::shared::{answer::Answer, file_parser::get_trimmed_input, registry::DayPart};
+
 #[derive(Debug)]
 struct Reflection {
     rows: Vec<String>,
@@ -127,9 +129,22 @@ fn solution_2(input: String) -> usize {
         .sum()
 }
 
+pub fn parts() -> Vec<DayPart> {
+    vec![
+        DayPart::new(2023, 13, 1, || {
+            Answer::from(solution_1(get_trimmed_input(file!(), "input1.txt")))
+        })
+        .expecting(29846),
+        DayPart::new(2023, 13, 2, || {
+            Answer::from(solution_2(get_trimmed_input(file!(), "input1.txt")))
+        })
+        .expecting(25401),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::shared::file_parser::get_input;
+    use crate::shared::file_parser::get_trimmed_input;
 
     use super::*;
 
@@ -175,25 +190,25 @@ mod tests {
 
     #[test]
     fn example_1_test() {
-        let input = get_input(file!(), "example1.txt");
+        let input = get_trimmed_input(file!(), "example1.txt");
         assert_eq!(405, solution_1(input));
     }
 
     #[test]
     fn solution_1_test() {
-        let input = get_input(file!(), "input1.txt");
+        let input = get_trimmed_input(file!(), "input1.txt");
         assert_eq!(29846, solution_1(input));
     }
 
     #[test]
     fn example_2_test() {
-        let input = get_input(file!(), "example1.txt");
+        let input = get_trimmed_input(file!(), "example1.txt");
         assert_eq!(400, solution_2(input));
     }
 
     #[test]
     fn solution_2_test() {
-        let input = get_input(file!(), "input1.txt");
+        let input = get_trimmed_input(file!(), "input1.txt");
         assert_eq!(25401, solution_2(input));
     }
 }
@@ -1,8 +1,20 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    fmt,
     ops::{AddAssign, SubAssign},
 };
 
+use crate::{
+    shared::{
+        animation::{play, DayAnimation, Simulation},
+        answer::Answer,
+        file_parser::get_trimmed_input,
+        grid_renderer::GridRenderer,
+        registry::DayPart,
+    },
+    trace,
+};
+
 #[derive(PartialEq)]
 enum Space {
     Space,
@@ -72,6 +84,7 @@ fn solution_1(input: String) -> usize {
     platform.weigth_to_north()
 }
 
+#[derive(Clone)]
 struct PlatformSol2 {
     cube_coords: Vec<(usize, usize)>,
     ball_coords: Vec<(usize, usize)>,
@@ -204,7 +217,8 @@ impl PlatformSol2 {
             if loop_size == 0 {
                 if let Some(next) = cycle_map.get(cycle_start) {
                     loop_size.add_assign(1);
-                    println!(
+                    trace!(
+                        Debug,
                         "starting score: {}",
                         self.weigth_to_north_provide_list(&cycle_start)
                     );
@@ -217,12 +231,12 @@ impl PlatformSol2 {
                         }
                         current = next;
                     }
-                    println!("Starting cycle count: {}", cycle_count);
-                    println!("Loop size: {}", loop_size);
+                    trace!(Debug, "Starting cycle count: {}", cycle_count);
+                    trace!(Debug, "Loop size: {}", loop_size);
                     let remaining_cycles = (total_cycles - cycle_count) % loop_size;
-                    println!("Remaining cycles: {}", remaining_cycles);
+                    trace!(Debug, "Remaining cycles: {}", remaining_cycles);
                     cycle_count = total_cycles - remaining_cycles;
-                    println!("New cycle count: {}", cycle_count);
+                    trace!(Debug, "New cycle count: {}", cycle_count);
                     continue;
                 }
             }
@@ -247,33 +261,7 @@ impl PlatformSol2 {
     }
 
     fn print(&mut self) {
-        print!("\n\n");
-        let mut balls_and_cubes = self
-            .balls_and_cubes_chain()
-            .map(|c| (c.0, c.1))
-            .collect::<Vec<(usize, usize)>>();
-        balls_and_cubes.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
-        for y in 0..self.max_y + 1 {
-            for x in 0..self.max_x + 1 {
-                let matching_coord = balls_and_cubes
-                    .iter()
-                    .filter(|c| c.0.eq(&x) && c.1.eq(&y))
-                    .next();
-                match matching_coord {
-                    Some(coord) => {
-                        if self.ball_coords.contains(coord) {
-                            print!("0");
-                        } else {
-                            print!("#");
-                        }
-                    }
-                    None => print!("."),
-                }
-            }
-            print!("\n");
-        }
-
-        print!("Total value: {}\n", self.weigth_to_north());
+        trace!(Trace, "\n{}\nTotal value: {}", self, self.weigth_to_north());
     }
 
     fn set_balls(&mut self, test: &Vec<(usize, usize)>) {
@@ -281,6 +269,56 @@ impl PlatformSol2 {
     }
 }
 
+impl fmt::Display for PlatformSol2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let balls: HashSet<&(usize, usize)> = self.ball_coords.iter().collect();
+        let cubes: HashSet<&(usize, usize)> = self.cube_coords.iter().collect();
+        let renderer = GridRenderer::new(self.max_x + 1, self.max_y + 1, |x, y| {
+            if balls.contains(&(x, y)) {
+                'O'
+            } else if cubes.contains(&(x, y)) {
+                '#'
+            } else {
+                '.'
+            }
+        });
+        write!(f, "{}", renderer)
+    }
+}
+
+// Tilts the platform north, west, south and east for the given number of cycles
+#[derive(Clone)]
+struct TiltAnimation {
+    platform: PlatformSol2,
+    tilts: usize,
+    cycles: usize,
+}
+
+impl Simulation for TiltAnimation {
+    fn step(&mut self) -> bool {
+        if self.tilts == self.cycles * 4 {
+            return false;
+        }
+        match self.tilts % 4 {
+            0 => self.platform.north(),
+            1 => self.platform.west(),
+            2 => self.platform.south(),
+            _ => self.platform.east(),
+        }
+        self.tilts.add_assign(1);
+        true
+    }
+
+    fn frame(&self) -> String {
+        format!(
+            "{}\nTilts: {} Total value: {}",
+            self.platform,
+            self.tilts,
+            self.platform.weigth_to_north()
+        )
+    }
+}
+
 fn solution_2(input: String) -> usize {
     let platform = Platform::from(input);
     let mut platform2 = PlatformSol2::from(platform);
@@ -289,21 +327,52 @@ fn solution_2(input: String) -> usize {
     platform2.weigth_to_north()
 }
 
+pub fn parts() -> Vec<DayPart> {
+    vec![
+        DayPart::new(2023, 14, 1, || {
+            Answer::from(solution_1(get_trimmed_input(file!(), "input1.txt")))
+        })
+        .expecting(108889),
+        DayPart::new(2023, 14, 2, || {
+            Answer::from(solution_2(get_trimmed_input(file!(), "input1.txt")))
+        })
+        .expecting(104671),
+    ]
+}
+
+// Three spin cycles of the example platform, one tilt per frame
+pub fn animation() -> DayAnimation {
+    DayAnimation::new(2023, 14, |config| {
+        let platform =
+            PlatformSol2::from(Platform::from(get_trimmed_input(file!(), "example1.txt")));
+        let animation = TiltAnimation {
+            platform,
+            tilts: 0,
+            cycles: 3,
+        };
+        play(animation, config);
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::shared::file_parser::get_input;
+    use crate::shared::{
+        differential::{shrink_vec, Differential},
+        file_parser::get_trimmed_input,
+        random::Rng,
+    };
 
     use super::*;
 
     #[test]
     fn example_1_test() {
-        let input = get_input(file!(), "example1.txt");
+        let input = get_trimmed_input(file!(), "example1.txt");
         assert_eq!(136, solution_1(input));
     }
 
     #[test]
     fn example_1_test_use_sol_2() {
-        let input = get_input(file!(), "example1.txt");
+        let input = get_trimmed_input(file!(), "example1.txt");
         let platform = Platform::from(input);
         let mut platform2 = PlatformSol2::from(platform);
         platform2.north();
@@ -312,27 +381,119 @@ mod tests {
 
     #[test]
     fn solution_1_test() {
-        let input = get_input(file!(), "input1.txt");
+        let input = get_trimmed_input(file!(), "input1.txt");
         assert_eq!(108889, solution_1(input));
     }
     #[test]
     fn solution_1_test_use_sol_2() {
-        let input = get_input(file!(), "input1.txt");
+        let input = get_trimmed_input(file!(), "input1.txt");
         let platform = Platform::from(input);
         let mut platform2 = PlatformSol2::from(platform);
         platform2.north();
         assert_eq!(108889, platform2.weigth_to_north());
     }
 
+    #[test]
+    fn example_2_render_after_one_cycle() {
+        let input = get_trimmed_input(file!(), "example1.txt");
+        let mut platform2 = PlatformSol2::from(Platform::from(input));
+        platform2.north();
+        platform2.west();
+        platform2.south();
+        platform2.east();
+        assert_eq!(
+            [
+                ".....#....",
+                "....#...O#",
+                "...OO##...",
+                ".OO#......",
+                ".....OOO#.",
+                ".O#...O#.#",
+                "....O#....",
+                "......OOOO",
+                "#...O###..",
+                "#..OO#....",
+            ]
+            .join("\n"),
+            platform2.to_string()
+        );
+    }
+
     #[test]
     fn example_2_test() {
-        let input = get_input(file!(), "example1.txt");
+        let input = get_trimmed_input(file!(), "example1.txt");
         assert_eq!(64, solution_2(input));
     }
 
     #[test]
     fn solution_2_test() {
-        let input = get_input(file!(), "input1.txt");
+        let input = get_trimmed_input(file!(), "input1.txt");
         assert_eq!(104671, solution_2(input));
     }
+
+    // The rows of a platform, compared between the part 1 implementation and a
+    // single north tilt of the part 2 one
+    fn north_weight_differential() -> Differential<'static, Vec<String>, usize> {
+        Differential::new(
+            "Platform::weigth_to_north",
+            |rows: &Vec<String>| Platform::from(rows.join("\n")).weigth_to_north(),
+            "PlatformSol2::north",
+            |rows: &Vec<String>| {
+                let mut platform = PlatformSol2::from(Platform::from(rows.join("\n")));
+                platform.north();
+                platform.weigth_to_north()
+            },
+        )
+        .with_shrinker(|rows| {
+            let mut candidates = shrink_vec(rows, 1);
+            let width = rows[0].len();
+            if width > 1 {
+                for x in 0..width {
+                    candidates.push(
+                        rows.iter()
+                            .map(|row| format!("{}{}", &row[..x], &row[x + 1..]))
+                            .collect(),
+                    );
+                }
+            }
+            for (y, row) in rows.iter().enumerate() {
+                for (x, c) in row.char_indices().filter(|(_, c)| *c != '.') {
+                    let mut candidate = rows.clone();
+                    candidate[y] = format!("{}.{}", &row[..x], &row[x + c.len_utf8()..]);
+                    candidates.push(candidate);
+                }
+            }
+            candidates
+        })
+    }
+
+    fn random_platform(rng: &mut Rng) -> Vec<String> {
+        let width = rng.range(1..9);
+        (0..rng.range(1..9))
+            .map(|_| {
+                (0..width)
+                    .map(|_| *rng.pick(&['.', '.', 'O', '#']))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn differential_real_input() {
+        let input = get_trimmed_input(file!(), "input1.txt");
+        let rows = input.lines().map(String::from).collect::<Vec<String>>();
+        let examples = get_trimmed_input(file!(), "example1.txt");
+        let examples = examples.lines().map(String::from).collect::<Vec<String>>();
+        if let Err(disagreement) = north_weight_differential().check([rows, examples]) {
+            panic!("{}", disagreement);
+        }
+    }
+
+    #[test]
+    fn differential_random_input() {
+        let result = north_weight_differential().check_random(14, 2000, random_platform);
+        if let Err(disagreement) = result {
+            panic!("{}", disagreement);
+        }
+    }
 }
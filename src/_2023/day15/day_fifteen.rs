@@ -3,6 +3,8 @@ use std::{
     ops::{AddAssign, MulAssign},
 };
 
+use crate::shared::{answer::Answer, file_parser::get_trimmed_input, registry::DayPart};
+
 enum Operation {
     EQUAL(String, usize),
     DASH(String),
@@ -90,10 +92,29 @@ fn solution_2(input: String) -> usize {
     boxes.focusing_power()
 }
 
+pub fn parts() -> Vec<DayPart> {
+    vec![
+        DayPart::new(2023, 15, 1, || {
+            let input = get_trimmed_input(file!(), "input1.txt");
+            Answer::from(
+                input
+                    .split(',')
+                    .map(|sequence| String::from(sequence).custom_hash())
+                    .sum::<usize>(),
+            )
+        })
+        .expecting(517315),
+        DayPart::new(2023, 15, 2, || {
+            Answer::from(solution_2(get_trimmed_input(file!(), "input1.txt")))
+        })
+        .expecting(247763),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
 
-    use crate::shared::{file_parser::get_input, method_duration::log_method_duration};
+    use crate::shared::{file_parser::get_trimmed_input, method_duration::log_method_duration};
 
     use super::*;
 
@@ -105,7 +126,7 @@ mod tests {
 
     #[test]
     fn example_1_test() {
-        let input = get_input(file!(), "example1.txt");
+        let input = get_trimmed_input(file!(), "example1.txt");
         assert_eq!(
             1320usize,
             input
@@ -117,7 +138,7 @@ mod tests {
 
     #[test]
     fn solution_1_test() {
-        let input = get_input(file!(), "input1.txt");
+        let input = get_trimmed_input(file!(), "input1.txt");
         assert_eq!(
             517315usize,
             input
@@ -129,13 +150,13 @@ mod tests {
 
     #[test]
     fn example_2_test() {
-        let input = get_input(file!(), "example1.txt");
+        let input = get_trimmed_input(file!(), "example1.txt");
         assert_eq!(145, solution_2(input));
     }
 
     #[test]
     fn solution_2_test() {
-        let input = get_input(file!(), "input1.txt");
+        let input = get_trimmed_input(file!(), "input1.txt");
         assert_eq!(247763, log_method_duration(|| solution_2(input)));
     }
 }
@@ -1,11 +1,24 @@
 use std::{
     collections::{HashMap, HashSet},
+    fmt,
     ops::{AddAssign, ShlAssign, SubAssign},
     vec,
 };
 
 use itertools::concat;
 
+use crate::{
+    shared::{
+        animation::{play, DayAnimation, Simulation},
+        answer::Answer,
+        file_parser::get_trimmed_input,
+        grid_renderer::{Arrow, GridRenderer},
+        image_export::{DayImage, GridImage, Rgb},
+        registry::DayPart,
+    },
+    trace,
+};
+
 #[derive(PartialEq, Eq, Clone)]
 enum RayDirection {
     Left,
@@ -14,6 +27,18 @@ enum RayDirection {
     Down,
 }
 
+impl RayDirection {
+    fn arrow(&self) -> Arrow {
+        match self {
+            RayDirection::Left => Arrow::Left,
+            RayDirection::Right => Arrow::Right,
+            RayDirection::Up => Arrow::Up,
+            RayDirection::Down => Arrow::Down,
+        }
+    }
+}
+
+#[derive(Clone)]
 struct Ray {
     coordinate: Coordinate,
     direction: RayDirection,
@@ -63,6 +88,7 @@ impl Ray {
     }
 }
 
+#[derive(Clone)]
 enum Encounter {
     HorizontalSplitter(Vec<RayDirection>),
     VerticalSplitter(Vec<RayDirection>),
@@ -148,7 +174,7 @@ impl From<char> for Encounter {
     }
 }
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(PartialEq, Eq, Hash, Clone)]
 struct Coordinate {
     x: usize,
     y: usize,
@@ -159,6 +185,7 @@ impl Coordinate {
     }
 }
 
+#[derive(Clone)]
 struct Contraption {
     map: HashMap<Coordinate, Encounter>,
     rays: Vec<Ray>,
@@ -193,28 +220,7 @@ impl From<String> for Contraption {
 
 impl Contraption {
     fn print(&self) {
-        for y in 0..self.max_y + 1 {
-            for x in 0..self.max_x + 1 {
-                let coordinate = Coordinate::new(x, y);
-                if self.energized_coordinates.contains(&coordinate) {
-                    print!("#");
-                } else {
-                    match self.map.get(&coordinate) {
-                        Some(encounter) => match encounter {
-                            Encounter::HorizontalSplitter(_) => print!("-"),
-                            Encounter::VerticalSplitter(_) => print!("|"),
-                            Encounter::LeftUpMirror(_) => print!("/"),
-                            Encounter::LeftDownMirror(_) => print!("\\"),
-                        },
-                        None => {
-                            print!(".");
-                        }
-                    }
-                }
-            }
-            print!("\n");
-        }
-        print!("\n\n\n");
+        trace!(Trace, "\n{}", self);
     }
 
     fn initialize_rays(&mut self, rays: Vec<Ray>) {
@@ -228,21 +234,81 @@ impl Contraption {
     fn shoot_rays_and_count_energized_spaces(&mut self) -> usize {
         while !self.rays.is_empty() {
             // self.print();
-            let mut new_rays = Vec::new();
-            for mut ray in self.rays.drain(..) {
-                self.energized_coordinates
-                    .insert(Coordinate::new(ray.coordinate.x, ray.coordinate.y));
-                if ray.next_coordinate(self.max_x, self.max_y) {
-                    match self.map.get_mut(&ray.coordinate) {
-                        Some(encounter) => new_rays.extend(encounter.get_next_rays(ray)),
-                        None => new_rays.push(ray),
-                    }
+            self.move_rays();
+        }
+        self.energized_coordinates.len()
+    }
+
+    fn move_rays(&mut self) {
+        let mut new_rays = Vec::new();
+        for mut ray in self.rays.drain(..) {
+            self.energized_coordinates
+                .insert(Coordinate::new(ray.coordinate.x, ray.coordinate.y));
+            if ray.next_coordinate(self.max_x, self.max_y) {
+                match self.map.get_mut(&ray.coordinate) {
+                    Some(encounter) => new_rays.extend(encounter.get_next_rays(ray)),
+                    None => new_rays.push(ray),
                 }
             }
+        }
 
-            self.rays = new_rays;
+        self.rays = new_rays;
+    }
+
+    // Energized tiles in yellow, mirrors and splitters the beam missed in grey
+    fn energized_image(&self) -> GridImage<impl Fn(usize, usize) -> Rgb + '_> {
+        GridImage::new(self.max_x + 1, self.max_y + 1, |x, y| {
+            let coordinate = Coordinate::new(x, y);
+            if self.energized_coordinates.contains(&coordinate) {
+                Rgb::YELLOW
+            } else if self.map.contains_key(&coordinate) {
+                Rgb::GREY
+            } else {
+                Rgb::WHITE
+            }
+        })
+    }
+}
+
+impl Simulation for Contraption {
+    fn step(&mut self) -> bool {
+        if self.rays.is_empty() {
+            return false;
         }
-        self.energized_coordinates.len()
+        self.move_rays();
+        true
+    }
+
+    fn frame(&self) -> String {
+        format!(
+            "{:#}\nEnergized: {}",
+            self,
+            self.energized_coordinates.len()
+        )
+    }
+}
+
+impl fmt::Display for Contraption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let renderer = GridRenderer::new(self.max_x + 1, self.max_y + 1, |x, y| {
+            match self.map.get(&Coordinate::new(x, y)) {
+                Some(Encounter::HorizontalSplitter(_)) => '-',
+                Some(Encounter::VerticalSplitter(_)) => '|',
+                Some(Encounter::LeftUpMirror(_)) => '/',
+                Some(Encounter::LeftDownMirror(_)) => '\\',
+                None => '.',
+            }
+        })
+        .with_visited(
+            self.energized_coordinates.iter().map(|c| (c.x, c.y)),
+            Some('#'),
+        )
+        .with_arrows(
+            self.rays
+                .iter()
+                .map(|ray| ((ray.coordinate.x, ray.coordinate.y), ray.direction.arrow())),
+        );
+        write!(f, "{}", renderer)
     }
 }
 
@@ -288,39 +354,83 @@ fn solution_2(input: String) -> usize {
     max_energized
 }
 
+pub fn parts() -> Vec<DayPart> {
+    vec![
+        DayPart::new(2023, 16, 1, || {
+            Answer::from(solution_1(get_trimmed_input(file!(), "input1.txt")))
+        })
+        .expecting(7046),
+        DayPart::new(2023, 16, 2, || {
+            Answer::from(solution_2(get_trimmed_input(file!(), "input1.txt")))
+        })
+        .expecting(7313),
+    ]
+}
+
+// The beam of part one entering the example contraption
+pub fn animation() -> DayAnimation {
+    DayAnimation::new(2023, 16, |config| {
+        let mut contraption = Contraption::from(get_trimmed_input(file!(), "example1.txt"));
+        contraption.rays.push(Ray::new(0, 0, RayDirection::Right));
+        play(contraption, config);
+    })
+}
+
+// The tiles the beam of part one energizes
+pub fn image() -> DayImage {
+    DayImage::new(2023, 16, |path, config| {
+        let mut contraption = Contraption::from(get_trimmed_input(file!(), "input1.txt"));
+        contraption.rays.push(Ray::new(0, 0, RayDirection::Right));
+        contraption.shoot_rays_and_count_energized_spaces();
+        let image = contraption.energized_image();
+        image.save(path, config)
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::shared::file_parser::get_input;
+    use crate::shared::file_parser::get_trimmed_input;
 
     use super::*;
 
     #[test]
     fn example_1_test() {
-        let input = get_input(file!(), "example1.txt");
+        let input = get_trimmed_input(file!(), "example1.txt");
         assert_eq!(46, solution_1(input));
     }
 
+    #[test]
+    fn example_1_render_energized() {
+        let input = get_trimmed_input(file!(), "example1.txt");
+        let mut contraption = Contraption::from(input);
+        contraption.rays.push(Ray::new(0, 0, RayDirection::Right));
+        contraption.shoot_rays_and_count_energized_spaces();
+        let rendered = contraption.to_string();
+        assert_eq!(46, rendered.matches('#').count());
+        assert_eq!("######....", rendered.lines().next().unwrap());
+    }
+
     #[test]
     fn solution_1_test() {
-        let input = get_input(file!(), "input1.txt");
+        let input = get_trimmed_input(file!(), "input1.txt");
         assert_eq!(7046, solution_1(input));
     }
 
     #[test]
     fn example_2_test() {
-        let input = get_input(file!(), "example1.txt");
+        let input = get_trimmed_input(file!(), "example1.txt");
         assert_eq!(51, solution_2(input));
     }
 
     #[test]
     fn solution_2_test() {
-        let input = get_input(file!(), "input1.txt");
+        let input = get_trimmed_input(file!(), "input1.txt");
         assert_eq!(7313, solution_2(input));
     }
 
     #[test]
     fn example_2_test_individual_solution() {
-        let input = get_input(file!(), "example1.txt");
+        let input = get_trimmed_input(file!(), "example1.txt");
         let mut contraption = Contraption::from(input);
         contraption.initialize_rays(vec![Ray::new(3, 0, RayDirection::Down)]);
         let result = contraption.shoot_rays_and_count_energized_spaces();
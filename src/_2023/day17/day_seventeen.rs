@@ -1,10 +1,21 @@
 use std::{
     cmp::Ordering,
     collections::{BinaryHeap, HashMap},
-    ops::AddAssign,
+    fmt,
     thread::panicking,
 };
 
+use crate::{
+    shared::{
+        answer::Answer,
+        file_parser::get_input,
+        grid_renderer::GridRenderer,
+        image_export::{DayImage, GridImage, Rgb},
+        registry::DayPart,
+    },
+    trace,
+};
+
 trait FromString {
     fn custom_from(input: String) -> Self;
 }
@@ -91,34 +102,9 @@ fn dijkstra_with_directions(
                 path.push(u);
             }
             path.reverse();
-            let mut after_calculation = 0;
-            for y in 0..max_y + 1 {
-                for x in 0..max_x + 1 {
-                    let index = path.iter().position(|coords| coords.eq(&(x, y)));
-                    match index {
-                        Some(index) => {
-                            if index == 0 {
-                                print!("{}", grid.get(y).unwrap().get(x).unwrap());
-                                continue;
-                            }
-                            after_calculation.add_assign(grid.get(y).unwrap().get(x).unwrap());
-                            let from = path.get(index - 1).unwrap();
-                            let to = path.get(index).unwrap();
-                            let direction = direction_from_coords(*from, *to);
-                            match direction {
-                                Direction::Right => print!(">"),
-                                Direction::Left => print!("<"),
-                                Direction::Up => print!("^"),
-                                Direction::Down => print!("v"),
-                                Direction::None => panic!("{:?} should not exist", to),
-                            }
-                        }
-                        None => print!("{}", grid.get(y).unwrap().get(x).unwrap()),
-                    }
-                }
-                print!("\n");
-            }
-            println!("{}", after_calculation);
+            let after_calculation: usize = path.iter().skip(1).map(|(x, y)| grid[*y][*x]).sum();
+            trace!(Trace, "\n{}", render_path(grid, &path));
+            trace!(Debug, "Recalculated cost: {}", after_calculation);
             return Some((path, cost));
         }
 
@@ -152,6 +138,22 @@ fn dijkstra_with_directions(
     None
 }
 
+fn render_path<'a>(grid: &'a Cities, path: &[(usize, usize)]) -> impl fmt::Display + 'a {
+    GridRenderer::new(grid[0].len(), grid.len(), |x, y| {
+        char::from_digit(grid[y][x] as u32, 10).unwrap()
+    })
+    .with_path(path)
+}
+
+// Heat loss as shades of grey with the path drawn in red
+fn path_image<'a>(
+    grid: &'a Cities,
+    path: &[(usize, usize)],
+) -> GridImage<impl Fn(usize, usize) -> Rgb + 'a> {
+    GridImage::new(grid[0].len(), grid.len(), |x, y| Rgb::shade(grid[y][x], 9))
+        .with_path(path, Rgb::RED)
+}
+
 fn get_neighbors(
     position: (usize, usize),
     max_x: usize,
@@ -195,21 +197,50 @@ fn solution_1(cities: Cities) -> Option<usize> {
 
     match result {
         Some((path, cost)) => {
-            println!("Path: {:?}", path);
-            println!("Cost: {}", cost);
+            trace!(Debug, "Path: {:?}", path);
+            trace!(Debug, "Cost: {}", cost);
             return Some(cost);
         }
-        None => println!("No path found"),
+        None => trace!(Warn, "No path found"),
     }
     None
 }
 
+// The answer of part one is not known yet, the solution doesn't give the
+// right one
+pub fn parts() -> Vec<DayPart> {
+    vec![DayPart::new(2023, 17, 1, || {
+        Answer::from(solution_1(Cities::custom_from(get_input(
+            file!(),
+            "input1.txt",
+        ))))
+    })]
+}
+
+// The path part one finds through the real input, over the heat losses
+pub fn image() -> DayImage {
+    DayImage::new(2023, 17, |path, config| {
+        let cities = Cities::custom_from(get_input(file!(), "input1.txt"));
+        let end = (cities[0].len() - 1, cities.len() - 1);
+        let (route, _) = dijkstra_with_directions(&cities, (0, 0), end).unwrap_or_default();
+        let image = path_image(&cities, &route);
+        image.save(path, config)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::shared::file_parser::get_input;
 
     use super::*;
 
+    #[test]
+    fn render_path_test() {
+        let cities = Cities::custom_from(String::from("123\n456"));
+        let path = vec![(0, 0), (1, 0), (1, 1), (2, 1)];
+        assert_eq!("1>3\n4v>", render_path(&cities, &path).to_string());
+    }
+
     #[test]
     fn example_1_test() {
         let input = get_input(file!(), "example1.txt");
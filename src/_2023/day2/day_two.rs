@@ -1,287 +1,265 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-#[derive(Eq, Hash, PartialEq, Debug)]
-enum CubeColor {
-    RED,
-    BLUE,
-    GREEN,
+use crate::shared::{
+    answer::Answer,
+    file_parser::{get_input, get_rows},
+    registry::DayPart,
+};
+
+// Cubes by colour, any colour name works and a colour that isn't listed has
+// no cubes
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Bag {
+    cubes: BTreeMap<String, i32>,
+}
+
+// A cube set as written in a game: "3 blue, 4 red"
+impl From<&str> for Bag {
+    fn from(cube_set: &str) -> Self {
+        let mut bag = Bag::default();
+        for cubes in cube_set
+            .split(", ")
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+        {
+            let (count, colour) = cubes.split_once(' ').expect("Cubes without a colour");
+            let count: i32 = count.parse().expect("Cube count is not a number");
+            *bag.cubes.entry(colour.trim().to_owned()).or_insert(0) += count;
+        }
+        bag
+    }
+}
+
+impl Bag {
+    fn count(&self, colour: &str) -> i32 {
+        self.cubes.get(colour).copied().unwrap_or(0)
+    }
+
+    // Whether every cube of `other` could have been drawn from this bag
+    fn contains(&self, other: &Bag) -> bool {
+        other
+            .cubes
+            .iter()
+            .all(|(colour, count)| self.count(colour) >= *count)
+    }
+
+    // The smallest bag containing both bags
+    fn union(mut self, other: &Bag) -> Bag {
+        for (colour, count) in &other.cubes {
+            let cubes = self.cubes.entry(colour.clone()).or_insert(0);
+            *cubes = (*cubes).max(*count);
+        }
+        self
+    }
+
+    // Colours missing from the bag count as zero cubes
+    fn power<'a>(&self, colours: impl IntoIterator<Item = &'a str>) -> i32 {
+        colours
+            .into_iter()
+            .map(|colour| self.count(colour))
+            .product()
+    }
 }
 
 #[derive(Eq, PartialEq, Debug)]
 struct Game {
     id: i32,
-    cube_sets: Vec<HashMap<CubeColor, i32>>,
+    cube_sets: Vec<Bag>,
 }
 
 impl From<String> for Game {
     fn from(row: String) -> Self {
-        let id: i32;
-        let mut cube_sets = Vec::<HashMap<CubeColor, i32>>::new();
-        let mut game_id_and_cube_sets = row.split(": ");
-        match game_id_and_cube_sets.next() {
-            Some(game_id) => match game_id.strip_prefix("Game ") {
-                Some(id_str) => id = id_str.parse().unwrap(),
-                None => todo!(),
-            },
-            None => todo!(),
-        }
-        match game_id_and_cube_sets.next() {
-            Some(cube_sets_as_str) => {
-                for cube_set in cube_sets_as_str.split("; ") {
-                    let mut cube_set_to_add = HashMap::<CubeColor, i32>::new();
-                    for color in cube_set.split(", ") {
-                        if color.ends_with(" blue") {
-                            cube_set_to_add.insert(
-                                CubeColor::BLUE,
-                                color.replace(" blue", "").parse().unwrap(),
-                            );
-                        } else if color.ends_with(" red") {
-                            cube_set_to_add
-                                .insert(CubeColor::RED, color.replace(" red", "").parse().unwrap());
-                        } else if color.ends_with(" green") {
-                            cube_set_to_add.insert(
-                                CubeColor::GREEN,
-                                color.replace(" green", "").parse().unwrap(),
-                            );
-                        }
-                    }
-                    cube_sets.push(cube_set_to_add);
-                }
-            }
-            None => todo!(),
-        }
-
+        let (game_id, cube_sets) = row.split_once(": ").expect("Game without cube sets");
+        let id = game_id
+            .strip_prefix("Game ")
+            .and_then(|id| id.parse().ok())
+            .expect("Game without an id");
+        let cube_sets = cube_sets.split("; ").map(Bag::from).collect();
         Game { id, cube_sets }
     }
 }
 
-struct OptimizedGameSol1 {
-    id: i32,
-    max_red: i32,
-    max_blue: i32,
-    max_green: i32,
+impl Game {
+    fn minimal_bag(&self) -> Bag {
+        self.cube_sets
+            .iter()
+            .fold(Bag::default(), |bag, cube_set| bag.union(cube_set))
+    }
+
+    fn fits(&self, bag: &Bag) -> bool {
+        self.cube_sets.iter().all(|cube_set| bag.contains(cube_set))
+    }
 }
 
-impl OptimizedGameSol1 {
-    fn from(game: &Game) -> Self {
-        let mut max_red: i32 = 0;
-        let mut max_blue: i32 = 0;
-        let mut max_green: i32 = 0;
-        for cube_set in &game.cube_sets {
-            for (color, count) in cube_set {
-                match color {
-                    CubeColor::RED => {
-                        if count > &max_red {
-                            max_red = count.to_owned();
-                        }
-                    }
-                    CubeColor::BLUE => {
-                        if count > &max_blue {
-                            max_blue = count.to_owned();
-                        }
-                    }
-                    CubeColor::GREEN => {
-                        if count > &max_green {
-                            max_green = count.to_owned();
-                        }
-                    }
-                }
-            }
-        }
+// The smallest bag every game of the set fits in
+#[cfg(test)]
+fn minimal_bag(games: &[Game]) -> Bag {
+    games
+        .iter()
+        .fold(Bag::default(), |bag, game| bag.union(&game.minimal_bag()))
+}
 
-        OptimizedGameSol1 {
-            id: game.id,
-            max_red,
-            max_blue,
-            max_green,
-        }
-    }
+fn colours(games: &[Game]) -> BTreeSet<&str> {
+    games
+        .iter()
+        .flat_map(|game| game.cube_sets.iter())
+        .flat_map(|cube_set| cube_set.cubes.keys())
+        .map(String::as_str)
+        .collect()
+}
 
-    fn is_possible(this: &Self, bag: &HashMap<CubeColor, i32>) -> bool {
-        let mut is_possible = true;
-        for (cube_color, cube_amount) in bag {
-            match cube_color {
-                CubeColor::RED => is_possible = this.max_red <= *cube_amount,
-                CubeColor::BLUE => is_possible = this.max_blue <= *cube_amount,
-                CubeColor::GREEN => is_possible = this.max_green <= *cube_amount,
-            }
-            if !is_possible {
-                break;
-            }
-        }
-        is_possible
+// The bags in which exactly a chosen subset of the games is possible: at least
+// the minimal bag of the subset, and for every other game at least one colour
+// with fewer cubes than that game needs
+#[cfg(test)]
+#[derive(Debug, PartialEq)]
+struct ExactBags {
+    at_least: Bag,
+    excluded: Vec<Bag>,
+}
+
+#[cfg(test)]
+impl ExactBags {
+    fn contains(&self, bag: &Bag) -> bool {
+        bag.contains(&self.at_least) && self.excluded.iter().all(|other| !bag.contains(other))
     }
+}
 
-    fn power(this: &Self) -> i32 {
-        this.max_red * this.max_blue * this.max_green
+// None when no bag allows the subset without some other game. A game fits in
+// every bag larger than its minimal bag, so when any bag works the minimal bag
+// of the subset does.
+#[cfg(test)]
+fn bags_allowing_exactly(games: &[Game], ids: &[i32]) -> Option<ExactBags> {
+    let (chosen, others): (Vec<&Game>, Vec<&Game>) =
+        games.iter().partition(|game| ids.contains(&game.id));
+    let at_least = chosen
+        .iter()
+        .fold(Bag::default(), |bag, game| bag.union(&game.minimal_bag()));
+    let excluded: Vec<Bag> = others.iter().map(|game| game.minimal_bag()).collect();
+    if excluded.iter().any(|other| at_least.contains(other)) {
+        return None;
     }
+    Some(ExactBags { at_least, excluded })
 }
 
 fn extract_games_from_rows(rows: Vec<String>) -> Vec<Game> {
     rows.iter()
-        .filter(|row| row.len() > 0)
+        .filter(|row| !row.is_empty())
         .map(|row| Game::from(row.to_string()))
         .collect()
 }
 
-fn solution_1(games: Vec<Game>, bag: HashMap<CubeColor, i32>) -> i32 {
+fn solution_1(games: Vec<Game>, bag: Bag) -> i32 {
     games
-        .into_iter()
-        .map(|game| OptimizedGameSol1::from(&game))
-        .filter(|optimized_game| OptimizedGameSol1::is_possible(optimized_game, &bag))
-        .map(|optimized_game| optimized_game.id)
+        .iter()
+        .filter(|game| game.fits(&bag))
+        .map(|game| game.id)
         .sum()
 }
 
 fn solution_2(games: Vec<Game>) -> i32 {
+    let colours = colours(&games);
     games
-        .into_iter()
-        .map(|game| OptimizedGameSol1::from(&game))
-        .map(|optimized_game| OptimizedGameSol1::power(&optimized_game))
+        .iter()
+        .map(|game| game.minimal_bag().power(colours.iter().copied()))
         .sum()
 }
 
+pub fn parts() -> Vec<DayPart> {
+    let games = || extract_games_from_rows(get_rows(get_input(file!(), "input1.txt")));
+    vec![
+        DayPart::new(2023, 2, 1, move || {
+            Answer::from(solution_1(games(), Bag::from("12 red, 13 green, 14 blue")))
+        })
+        .expecting(3059),
+        DayPart::new(2023, 2, 2, move || Answer::from(solution_2(games()))).expecting(65371),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
-    use std::vec;
-
-    use crate::shared::file_parser::{get_input, get_rows};
+    use crate::shared::{
+        file_parser::{get_input, get_rows},
+        readme::check_readme_examples,
+    };
 
     use super::*;
 
-    fn create_bag(red: i32, blue: i32, green: i32) -> HashMap<CubeColor, i32> {
-        vec![
-            (CubeColor::RED, red),
-            (CubeColor::GREEN, green),
-            (CubeColor::BLUE, blue),
-        ]
-        .into_iter()
-        .collect()
-    }
+    const BAG: &str = "12 red, 13 green, 14 blue";
 
-    fn create_optimized_game(red: i32, blue: i32, green: i32) -> OptimizedGameSol1 {
-        OptimizedGameSol1 {
-            id: 0,
-            max_red: red,
-            max_blue: blue,
-            max_green: green,
-        }
+    fn example_games() -> Vec<Game> {
+        extract_games_from_rows(get_rows(get_input(file!(), "example1.txt")))
     }
 
     #[test]
     fn test_parser() {
-        let examples = vec![(
+        let game = Game::from(String::from(
             "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
-            Game {
-                id: 1,
-                cube_sets: vec![
-                    vec![(CubeColor::BLUE, 3), (CubeColor::RED, 4)]
-                        .into_iter()
-                        .collect(),
-                    vec![
-                        (CubeColor::RED, 1),
-                        (CubeColor::GREEN, 2),
-                        (CubeColor::BLUE, 6),
-                    ]
-                    .into_iter()
-                    .collect(),
-                    vec![(CubeColor::GREEN, 2)].into_iter().collect(),
-                ],
-            },
-        )];
-        for (input, expected) in examples {
-            assert_eq!(expected, Game::from(input.to_owned()));
-        }
-    }
-
-    #[test]
-    fn test_optimizer_day_one() {
-        let game = Game {
-            id: 1,
-            cube_sets: vec![
-                vec![(CubeColor::BLUE, 3), (CubeColor::RED, 4)]
-                    .into_iter()
-                    .collect(),
-                vec![
-                    (CubeColor::RED, 1),
-                    (CubeColor::GREEN, 2),
-                    (CubeColor::BLUE, 6),
-                ]
-                .into_iter()
-                .collect(),
-                vec![(CubeColor::GREEN, 2)].into_iter().collect(),
+        ));
+        assert_eq!(1, game.id);
+        assert_eq!(
+            vec![
+                Bag::from("4 red, 3 blue"),
+                Bag::from("6 blue, 2 green, 1 red"),
+                Bag::from("2 green"),
             ],
-        };
-        let optimized_game = OptimizedGameSol1::from(&game);
-        assert_eq!(optimized_game.id, game.id);
-        assert_eq!(optimized_game.max_red, 4);
-        assert_eq!(optimized_game.max_green, 2);
-        assert_eq!(optimized_game.max_blue, 6);
+            game.cube_sets
+        );
+        let game = Game::from(String::from("Game 12: 7 purple; 1 octarine, 2 purple"));
+        assert_eq!(12, game.id);
+        assert_eq!(Bag::from("1 octarine, 7 purple"), game.minimal_bag());
     }
 
     #[test]
-    fn test_is_possible() {
-        let balls_in_bag = create_bag(15, 15, 15);
-        let game_1 = create_optimized_game(5, 5, 5);
-
-        assert!(
-            OptimizedGameSol1::is_possible(&game_1, &balls_in_bag),
-            "This should be possible"
+    fn test_minimal_bag() {
+        let game = Game::from(String::from(
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+        ));
+        let bag = game.minimal_bag();
+        assert_eq!(Bag::from("4 red, 2 green, 6 blue"), bag);
+        assert_eq!(48, bag.power(["red", "green", "blue"]));
+        assert_eq!(0, bag.power(["red", "purple"]));
+        assert!(game.fits(&bag));
+        assert!(game.fits(&Bag::from("5 red, 5 green, 6 blue, 1 purple")));
+        assert!(!game.fits(&Bag::from("4 red, 1 green, 6 blue")));
+        assert_eq!(
+            Bag::from("20 red, 13 green, 15 blue"),
+            minimal_bag(&example_games())
         );
+    }
 
-        let balls_in_bag = create_bag(5, 5, 5);
-        assert!(
-            OptimizedGameSol1::is_possible(&game_1, &balls_in_bag),
-            "This should be possible"
-        );
+    #[test]
+    fn test_bags_allowing_exactly() {
+        let games = example_games();
+        let bags = bags_allowing_exactly(&games, &[1, 2, 5]).unwrap();
+        assert_eq!(Bag::from("6 red, 3 green, 6 blue"), bags.at_least);
+        assert!(bags.contains(&Bag::from(BAG)));
+        assert!(bags.contains(&bags.at_least));
+        assert!(!bags.contains(&Bag::from("20 red, 13 green, 14 blue")));
+        assert!(!bags.contains(&Bag::from("6 red, 3 green, 5 blue")));
+        // every bag that allows game 3 allows game 1 as well
+        assert_eq!(None, bags_allowing_exactly(&games, &[3]));
+        let nothing = bags_allowing_exactly(&games, &[]).unwrap();
+        assert!(nothing.contains(&Bag::default()));
+        assert!(!nothing.contains(&Bag::from(BAG)));
     }
 
     #[test]
     fn example_1() {
-        let balls_in_bag = create_bag(12, 14, 13);
-
-        let input = get_input(file!(), "example1.txt");
-        let rows = get_rows(input);
-        assert_eq!(8, solution_1(extract_games_from_rows(rows), balls_in_bag));
+        assert_eq!(8, solution_1(example_games(), Bag::from(BAG)));
     }
 
     #[test]
     fn solution_1_test() {
-        let balls_in_bag = create_bag(12, 14, 13);
-
         let input = get_input(file!(), "input1.txt");
         let rows = get_rows(input);
-        let solution = solution_1(extract_games_from_rows(rows), balls_in_bag);
+        let solution = solution_1(extract_games_from_rows(rows), Bag::from(BAG));
         assert_eq!(3059, solution);
     }
 
-    #[test]
-    fn test_power() {
-        let game = Game {
-            id: 1,
-            cube_sets: vec![
-                vec![(CubeColor::BLUE, 3), (CubeColor::RED, 4)]
-                    .into_iter()
-                    .collect(),
-                vec![
-                    (CubeColor::RED, 1),
-                    (CubeColor::GREEN, 2),
-                    (CubeColor::BLUE, 6),
-                ]
-                .into_iter()
-                .collect(),
-                vec![(CubeColor::GREEN, 2)].into_iter().collect(),
-            ],
-        };
-        let optimized_game = OptimizedGameSol1::from(&game);
-        assert_eq!(48, OptimizedGameSol1::power(&optimized_game));
-    }
-
     #[test]
     fn example_2() {
-        let input = get_input(file!(), "example1.txt");
-        let rows = get_rows(input);
-        assert_eq!(2286, solution_2(extract_games_from_rows(rows)));
+        assert_eq!(2286, solution_2(example_games()));
     }
 
     #[test]
@@ -291,4 +269,15 @@ mod tests {
         let solution = solution_2(extract_games_from_rows(rows));
         assert_eq!(65371, solution);
     }
+
+    #[test]
+    fn readme_examples() {
+        check_readme_examples(file!(), &["example1.txt"], "input1.txt", |part, input| {
+            let games = extract_games_from_rows(get_rows(input));
+            match part {
+                1 => solution_1(games, Bag::from(BAG)),
+                _ => solution_2(games),
+            }
+        });
+    }
 }
@@ -1,46 +1,12 @@
-use std::collections::{HashMap, HashSet};
-
-#[derive(Debug, PartialEq, Eq, Hash)]
-enum SchemaValue {
-    Number(usize),
-    NumberPlaceHolder,
-    Symbol(char),
-    Empty,
-}
-
-impl SchemaValue {
-    fn get_number_coordinate(content: &SchemaContent, coord: Coordinate) -> Option<Coordinate> {
-        match content.get(&coord) {
-            Some(schema_value) => match schema_value {
-                SchemaValue::Number(value) => Option::Some(coord),
-                SchemaValue::NumberPlaceHolder => SchemaValue::get_number_coordinate(
-                    content,
-                    Coordinate {
-                        x: coord.x + 1,
-                        y: coord.y,
-                    },
-                ),
-                SchemaValue::Symbol(_) => Option::None,
-                SchemaValue::Empty => Option::None,
-            },
-            None => Option::None,
-        }
-    }
+use std::collections::HashMap;
 
-    fn get_value(content: &SchemaContent, coord: Coordinate) -> usize {
-        match content.get(&coord) {
-            Some(schema_value) => match schema_value {
-                SchemaValue::Number(number) => *number,
-                SchemaValue::NumberPlaceHolder => 0,
-                SchemaValue::Symbol(_) => 0,
-                SchemaValue::Empty => 0,
-            },
-            None => 0,
-        }
-    }
-}
+use crate::shared::{
+    answer::Answer,
+    file_parser::{get_input, get_rows},
+    registry::DayPart,
+};
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 struct Coordinate {
     x: usize,
     y: usize,
@@ -52,229 +18,245 @@ impl Coordinate {
     }
 }
 
-type SchemaContent = HashMap<Coordinate, SchemaValue>;
+// A number of the schematic, starting at its leftmost digit
+#[derive(Debug, PartialEq)]
+struct NumberSpan {
+    value: usize,
+    start: Coordinate,
+    length: usize,
+}
+
+impl NumberSpan {
+    // The cells around the number, including the ones off the schematic on
+    // the right and bottom side
+    fn neighbours(&self) -> impl Iterator<Item = Coordinate> + '_ {
+        let Coordinate { x, y } = self.start;
+        let columns = x.saturating_sub(1)..=x + self.length;
+        let rows = y.saturating_sub(1)..=y + 1;
+        rows.flat_map(move |row| {
+            columns
+                .clone()
+                .map(move |column| Coordinate::from(column, row))
+        })
+        .filter(move |cell| cell.y != y || cell.x < x || cell.x >= x + self.length)
+    }
+}
 
+#[derive(Debug, PartialEq)]
+struct Symbol {
+    character: char,
+    coordinate: Coordinate,
+}
+
+// Every number is read once as a span, adjacency between numbers and symbols
+// is indexed both ways by their position in `numbers` and `symbols`
 struct Schema {
-    content: SchemaContent,
+    numbers: Vec<NumberSpan>,
+    symbols: Vec<Symbol>,
+    symbols_of_number: Vec<Vec<usize>>,
+    numbers_of_symbol: Vec<Vec<usize>>,
 }
 
 impl Schema {
     fn from(rows: Vec<String>) -> Schema {
-        let mut content: SchemaContent = HashMap::new();
-        for (y, row) in rows.into_iter().enumerate() {
-            Schema::add_row_to_content(&mut content, y, row);
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            Schema::read_row(&mut numbers, &mut symbols, y, row);
         }
-        Schema { content }
-    }
-
-    fn add_row_to_content(content: &mut SchemaContent, y: usize, row: String) {
-        let mut current_number = String::new();
-
-        for (x, character) in row.chars().enumerate() {
-            if character.is_digit(10) {
-                content.insert(Coordinate { x, y }, SchemaValue::NumberPlaceHolder);
-                current_number.push(character);
-            } else {
-                if !current_number.is_empty() {
-                    if let Ok(number) = current_number.parse::<usize>() {
-                        content.insert(Coordinate { x: x - 1, y }, SchemaValue::Number(number));
-                    }
-                    current_number.clear();
-                }
-                if character.eq(&'.') {
-                    content.insert(Coordinate { x, y }, SchemaValue::Empty);
-                } else {
-                    content.insert(Coordinate { x, y }, SchemaValue::Symbol(character));
-                }
+        let symbol_at: HashMap<Coordinate, usize> = symbols
+            .iter()
+            .enumerate()
+            .map(|(index, symbol)| (symbol.coordinate, index))
+            .collect();
+        let mut symbols_of_number = vec![Vec::new(); numbers.len()];
+        let mut numbers_of_symbol = vec![Vec::new(); symbols.len()];
+        for (number, span) in numbers.iter().enumerate() {
+            for symbol in span.neighbours().filter_map(|cell| symbol_at.get(&cell)) {
+                symbols_of_number[number].push(*symbol);
+                numbers_of_symbol[*symbol].push(number);
             }
         }
-
-        // Check for the last number in the string
-        if !current_number.is_empty() {
-            if let Ok(number) = current_number.parse::<usize>() {
-                content.insert(
-                    Coordinate {
-                        x: row.len() - 1,
-                        y,
-                    },
-                    SchemaValue::Number(number),
-                );
-            }
+        Schema {
+            numbers,
+            symbols,
+            symbols_of_number,
+            numbers_of_symbol,
         }
     }
 
-    fn get_symbol_coords<'a>(&'a self, optional_symbol: Option<char>) -> Vec<&'a Coordinate> {
-        let mut coordinates = Vec::new();
-        for (coordinate, schema_value) in &self.content {
-            if let SchemaValue::Symbol(symbol) = schema_value {
-                match optional_symbol {
-                    Some(symbol_to_match) => {
-                        if symbol.eq(&symbol_to_match) {
-                            coordinates.push(coordinate);
-                        }
-                    }
-                    None => coordinates.push(coordinate),
-                }
+    fn read_row(numbers: &mut Vec<NumberSpan>, symbols: &mut Vec<Symbol>, y: usize, row: &str) {
+        let mut current: Option<NumberSpan> = None;
+        for (x, character) in row.chars().enumerate() {
+            if let Some(digit) = character.to_digit(10) {
+                let span = current.get_or_insert(NumberSpan {
+                    value: 0,
+                    start: Coordinate::from(x, y),
+                    length: 0,
+                });
+                span.value = span.value * 10 + digit as usize;
+                span.length += 1;
+                continue;
+            }
+            numbers.extend(current.take());
+            if character != '.' {
+                symbols.push(Symbol {
+                    character,
+                    coordinate: Coordinate::from(x, y),
+                });
             }
         }
-        coordinates
+        numbers.extend(current.take());
     }
 
-    fn get_adjacent_coords(coordinate: &Coordinate) -> Vec<Coordinate> {
-        let mut coords = Vec::new();
-        let x = coordinate.x;
-        let y = coordinate.y;
+    fn numbers_of(&self, symbol: usize) -> impl Iterator<Item = &NumberSpan> {
+        self.numbers_of_symbol[symbol]
+            .iter()
+            .map(|number| &self.numbers[*number])
+    }
 
-        if y > 0 && x > 0 {
-            coords.push(Coordinate::from(x - 1, y - 1));
-        }
-        if y > 0 {
-            coords.push(Coordinate::from(x, y - 1));
-            coords.push(Coordinate::from(x + 1, y - 1));
-        }
-        if x > 0 {
-            coords.push(Coordinate::from(x - 1, y));
-            coords.push(Coordinate::from(x - 1, y + 1));
-        }
-        coords.push(Coordinate::from(x + 1, y));
-        coords.push(Coordinate::from(x, y + 1));
-        coords.push(Coordinate::from(x + 1, y + 1));
-        coords
+    // Numbers next to at least one symbol, each number once
+    fn part_numbers(&self) -> impl Iterator<Item = &NumberSpan> {
+        self.numbers
+            .iter()
+            .zip(&self.symbols_of_number)
+            .filter(|(_, symbols)| !symbols.is_empty())
+            .map(|(number, _)| number)
     }
-}
 
-fn solution_1(schema: Schema) -> usize {
-    let symbol_coordinates = Schema::get_symbol_coords(&schema, Option::None);
-    let mut total = 0;
-    let mut adjacent_coordinates = HashSet::new();
-    for symbol_coordinate in symbol_coordinates {
-        for adjacant_coordinate in Schema::get_adjacent_coords(symbol_coordinate) {
-            adjacent_coordinates.insert(adjacant_coordinate);
-        }
+    #[cfg(test)]
+    fn isolated_numbers(&self) -> impl Iterator<Item = &NumberSpan> {
+        self.numbers
+            .iter()
+            .zip(&self.symbols_of_number)
+            .filter(|(_, symbols)| symbols.is_empty())
+            .map(|(number, _)| number)
     }
-    let mut number_coordinates = HashSet::new();
-    for adjacent_coordinate in adjacent_coordinates {
-        if let Some(coordinate) =
-            SchemaValue::get_number_coordinate(&schema.content, adjacent_coordinate)
-        {
-            number_coordinates.insert(coordinate);
-        }
+
+    // Numbers next to a symbol `character`, each number once
+    #[cfg(test)]
+    fn numbers_adjacent_to(&self, character: char) -> impl Iterator<Item = &NumberSpan> {
+        self.numbers
+            .iter()
+            .zip(&self.symbols_of_number)
+            .filter(move |(_, symbols)| {
+                symbols
+                    .iter()
+                    .any(|symbol| self.symbols[*symbol].character == character)
+            })
+            .map(|(number, _)| number)
     }
-    for number_coordinate in number_coordinates {
-        total += SchemaValue::get_value(&schema.content, number_coordinate);
+
+    // Symbols, optionally only `character`, with exactly `count` numbers
+    // around them, together with those numbers
+    fn symbols_with_numbers(
+        &self,
+        character: Option<char>,
+        count: usize,
+    ) -> impl Iterator<Item = (&Symbol, Vec<&NumberSpan>)> {
+        self.symbols
+            .iter()
+            .enumerate()
+            .filter(move |(_, symbol)| character.is_none_or(|c| c == symbol.character))
+            .filter(move |(index, _)| self.numbers_of_symbol[*index].len() == count)
+            .map(|(index, symbol)| (symbol, self.numbers_of(index).collect()))
     }
+}
 
-    total
+fn solution_1(schema: Schema) -> usize {
+    schema.part_numbers().map(|number| number.value).sum()
 }
 
+// A gear is a `*` with exactly two numbers around it
 fn solution_2(schema: Schema) -> usize {
-    let gear_coordinates = Schema::get_symbol_coords(&schema, Option::Some('*'));
-    let mut total = 0;
-    for gear_coordinate in gear_coordinates {
-        let mut adjacent_coordinates = HashSet::new();
-        for adjacant_coordinate in Schema::get_adjacent_coords(gear_coordinate) {
-            adjacent_coordinates.insert(adjacant_coordinate);
-        }
-        let mut number_coordinates = HashSet::new();
-        for adjacent_coordinate in adjacent_coordinates {
-            if let Some(coordinate) =
-                SchemaValue::get_number_coordinate(&schema.content, adjacent_coordinate)
-            {
-                number_coordinates.insert(coordinate);
-            }
-        }
-        if number_coordinates.len() != 2 {
-            continue;
-        }
-        let mut number_to_add = 1;
-        for number_coordinate in number_coordinates {
-            number_to_add *= SchemaValue::get_value(&schema.content, number_coordinate);
-        }
-
-        total += number_to_add;
-    }
+    schema
+        .symbols_with_numbers(Some('*'), 2)
+        .map(|(_, numbers)| numbers.iter().map(|number| number.value).product::<usize>())
+        .sum()
+}
 
-    total
+pub fn parts() -> Vec<DayPart> {
+    let schema = || Schema::from(get_rows(get_input(file!(), "input1.txt")));
+    vec![
+        DayPart::new(2023, 3, 1, move || Answer::from(solution_1(schema()))).expecting(527144),
+        DayPart::new(2023, 3, 2, move || Answer::from(solution_2(schema()))).expecting(81463996),
+    ]
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::shared::file_parser::{get_input, get_rows};
+    use crate::shared::{
+        file_parser::{get_input, get_rows},
+        readme::check_readme_examples,
+    };
 
     use super::*;
 
-    fn test_coord_value(content: &SchemaContent, coord_value: SchemaValue, x: usize, y: usize) {
-        assert_eq!(&coord_value, content.get(&Coordinate { x, y }).unwrap());
+    fn span(value: usize, x: usize, y: usize, length: usize) -> NumberSpan {
+        NumberSpan {
+            value,
+            start: Coordinate::from(x, y),
+            length,
+        }
+    }
+
+    fn example_schema() -> Schema {
+        Schema::from(get_rows(get_input(file!(), "example1.txt")))
+    }
+
+    fn values<'a>(numbers: impl Iterator<Item = &'a NumberSpan>) -> Vec<usize> {
+        numbers.map(|number| number.value).collect()
     }
 
     #[test]
-    fn example_1_test_row_1() {
-        let row = String::from("467..114..");
-        let schema = Schema::from(vec![row]);
-        let content = &schema.content;
-        test_coord_value(content, SchemaValue::NumberPlaceHolder, 0, 0);
-        test_coord_value(content, SchemaValue::NumberPlaceHolder, 1, 0);
-        test_coord_value(content, SchemaValue::Number(467), 2, 0);
-        test_coord_value(content, SchemaValue::Empty, 3, 0);
-        test_coord_value(content, SchemaValue::Empty, 4, 0);
-        test_coord_value(content, SchemaValue::NumberPlaceHolder, 5, 0);
-        test_coord_value(content, SchemaValue::NumberPlaceHolder, 6, 0);
-        test_coord_value(content, SchemaValue::Number(114), 7, 0);
-        test_coord_value(content, SchemaValue::Empty, 8, 0);
-        test_coord_value(content, SchemaValue::Empty, 9, 0);
-        let symbol_coords = Schema::get_symbol_coords(&schema, Option::None);
-        assert!(symbol_coords.is_empty());
-
-        let row = String::from("617*......");
-        let schema = Schema::from(vec![row]);
-        let content = &schema.content;
-        test_coord_value(content, SchemaValue::NumberPlaceHolder, 0, 0);
-        test_coord_value(content, SchemaValue::NumberPlaceHolder, 1, 0);
-        test_coord_value(content, SchemaValue::Number(617), 2, 0);
-        test_coord_value(content, SchemaValue::Symbol('*'), 3, 0);
-        test_coord_value(content, SchemaValue::Empty, 4, 0);
-        test_coord_value(content, SchemaValue::Empty, 5, 0);
-        test_coord_value(content, SchemaValue::Empty, 6, 0);
-        test_coord_value(content, SchemaValue::Empty, 7, 0);
-        test_coord_value(content, SchemaValue::Empty, 8, 0);
-        test_coord_value(content, SchemaValue::Empty, 9, 0);
-        let symbol_coords = Schema::get_symbol_coords(&schema, Option::None);
-        assert!(symbol_coords.contains(&&Coordinate { x: 3, y: 0 }));
-
-        let row = String::from("..$..+.58.");
-        let schema = Schema::from(vec![row]);
-        let content = &schema.content;
-        test_coord_value(content, SchemaValue::Empty, 0, 0);
-        test_coord_value(content, SchemaValue::Empty, 1, 0);
-        test_coord_value(content, SchemaValue::Symbol('$'), 2, 0);
-        test_coord_value(content, SchemaValue::Empty, 3, 0);
-        test_coord_value(content, SchemaValue::Empty, 4, 0);
-        test_coord_value(content, SchemaValue::Symbol('+'), 5, 0);
-        test_coord_value(content, SchemaValue::Empty, 6, 0);
-        test_coord_value(content, SchemaValue::NumberPlaceHolder, 7, 0);
-        test_coord_value(content, SchemaValue::Number(58), 8, 0);
-        test_coord_value(content, SchemaValue::Empty, 9, 0);
-        let symbol_coords = Schema::get_symbol_coords(&schema, Option::None);
-        assert!(symbol_coords.contains(&&Coordinate { x: 2, y: 0 }));
-        assert!(symbol_coords.contains(&&Coordinate { x: 5, y: 0 }));
-
-        let row = String::from("5432.5.+.584");
-        let schema = Schema::from(vec![row]);
-        let content = &schema.content;
-        test_coord_value(content, SchemaValue::NumberPlaceHolder, 0, 0);
-        test_coord_value(content, SchemaValue::NumberPlaceHolder, 1, 0);
-        test_coord_value(content, SchemaValue::NumberPlaceHolder, 2, 0);
-        test_coord_value(content, SchemaValue::Number(5432), 3, 0);
-        test_coord_value(content, SchemaValue::Empty, 4, 0);
-        test_coord_value(content, SchemaValue::Number(5), 5, 0);
-        test_coord_value(content, SchemaValue::Empty, 6, 0);
-        test_coord_value(content, SchemaValue::Symbol('+'), 7, 0);
-        test_coord_value(content, SchemaValue::Empty, 8, 0);
-        test_coord_value(content, SchemaValue::NumberPlaceHolder, 9, 0);
-        test_coord_value(content, SchemaValue::NumberPlaceHolder, 10, 0);
-        test_coord_value(content, SchemaValue::Number(584), 11, 0);
-        let symbol_coords = Schema::get_symbol_coords(&schema, Option::None);
-        assert!(symbol_coords.contains(&&Coordinate { x: 7, y: 0 }));
+    fn example_1_test_rows() {
+        let schema = Schema::from(vec![String::from("467..114.."), String::from("617*......")]);
+        assert_eq!(
+            vec![span(467, 0, 0, 3), span(114, 5, 0, 3), span(617, 0, 1, 3)],
+            schema.numbers
+        );
+        assert_eq!(
+            vec![Symbol {
+                character: '*',
+                coordinate: Coordinate::from(3, 1)
+            }],
+            schema.symbols
+        );
+        assert_eq!(vec![467, 617], values(schema.numbers_of(0)));
+        assert_eq!(vec![114], values(schema.isolated_numbers()));
+
+        // numbers at the end of a row, a number in the first column next to a
+        // symbol on the row above
+        let schema = Schema::from(vec![String::from("5432.5.+.584"), String::from("1..$")]);
+        assert_eq!(
+            vec![
+                span(5432, 0, 0, 4),
+                span(5, 5, 0, 1),
+                span(584, 9, 0, 3),
+                span(1, 0, 1, 1)
+            ],
+            schema.numbers
+        );
+        assert_eq!(vec![5432], values(schema.numbers_adjacent_to('$')));
+        assert_eq!(vec![5, 584, 1], values(schema.isolated_numbers()));
+    }
+
+    #[test]
+    fn example_queries() {
+        let schema = example_schema();
+        assert_eq!(vec![114, 58], values(schema.isolated_numbers()));
+        assert_eq!(
+            vec![467, 35, 617, 755, 598],
+            values(schema.numbers_adjacent_to('*'))
+        );
+        assert_eq!(vec![592], values(schema.numbers_adjacent_to('+')));
+        let lonely_gears: Vec<char> = schema
+            .symbols_with_numbers(Some('*'), 1)
+            .map(|(symbol, _)| symbol.character)
+            .collect();
+        assert_eq!(vec!['*'], lonely_gears);
+        assert_eq!(2, schema.symbols_with_numbers(Some('*'), 2).count());
+        assert_eq!(4, schema.symbols_with_numbers(None, 1).count());
     }
 
     #[test]
@@ -312,4 +294,15 @@ mod tests {
 
         assert_eq!(81463996, solution_2(schema));
     }
+
+    #[test]
+    fn readme_examples() {
+        check_readme_examples(file!(), &["example1.txt"], "input1.txt", |part, input| {
+            let schema = Schema::from(get_rows(input));
+            match part {
+                1 => solution_1(schema),
+                _ => solution_2(schema),
+            }
+        });
+    }
 }
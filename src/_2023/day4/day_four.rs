@@ -1,7 +1,9 @@
-use std::{
-    collections::{HashMap, HashSet},
-    mem,
-    ops::Add,
+use std::collections::HashSet;
+
+use crate::shared::{
+    answer::Answer,
+    file_parser::{get_input, get_rows},
+    registry::DayPart,
 };
 
 #[derive(Clone)]
@@ -81,53 +83,44 @@ fn solution_1(cards: &Vec<Card>) -> usize {
     count
 }
 
-struct Solution2Memoization {
-    card_id: usize,
-    generated_cards: usize,
-}
-
-struct Sol2Memo {
-    count: usize,
-    memo: Vec<Card>,
-}
-
-impl From<usize> for Sol2Memo {
-    fn from(value: usize) -> Self {
-        Sol2Memo {
-            count: value,
-            memo: Vec::new(),
+// Copies held of every card, indexed like `cards`. Only earlier cards win a
+// card, and the copies won go in a difference array: two updates per card.
+fn copies_per_card(cards: &[Card]) -> Vec<usize> {
+    let mut pending = vec![0isize; cards.len() + 1];
+    let mut per_card = Vec::with_capacity(cards.len());
+    let mut running = 0isize;
+    for (index, card) in cards.iter().enumerate() {
+        running += pending[index];
+        let copies = 1 + running as usize;
+        per_card.push(copies);
+
+        let last_won = (index + card.match_count()).min(cards.len() - 1);
+        if last_won > index {
+            pending[index + 1] += copies as isize;
+            pending[last_won + 1] -= copies as isize;
         }
     }
+    per_card
 }
 
-fn solution_2(
-    all_cards: &Vec<Card>,
-    cards_to_calculate: &Vec<Card>,
-    mut memo: HashMap<usize, usize>,
-    mut final_memo: HashMap<usize, usize>,
-) -> (HashMap<usize, usize>, HashMap<usize, usize>) {
-    for card in cards_to_calculate {
-        memo.entry(card.id).and_modify(|val| *val += 1).or_insert(1);
-
-        let match_count = Card::match_count(&card);
-        let mut new_cards = Vec::new();
-
-        for card_copy_index in (card.id)..(card.id + match_count) {
-            if let Some(card_copy) = all_cards.get(card_copy_index) {
-                new_cards.push(card_copy.clone());
-            }
-        }
+fn solution_2(cards: &[Card]) -> usize {
+    copies_per_card(cards).iter().sum()
+}
 
-        (memo, final_memo) = solution_2(all_cards, &new_cards, memo, final_memo);
-    }
-    (memo, final_memo)
+pub fn parts() -> Vec<DayPart> {
+    let cards = || Card::from_rows(get_rows(get_input(file!(), "input1.txt")));
+    vec![
+        DayPart::new(2023, 4, 1, move || Answer::from(solution_1(&cards()))).expecting(21821),
+        DayPart::new(2023, 4, 2, move || Answer::from(solution_2(&cards()))).expecting(5539496),
+    ]
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    use crate::shared::file_parser::{get_input, get_rows};
+    use crate::shared::{
+        file_parser::{get_input, get_rows},
+        method_duration::log_method_duration,
+    };
 
     use super::*;
 
@@ -170,8 +163,8 @@ mod tests {
         let input = get_input(file!(), "example1.txt");
         let rows = get_rows(input);
         let cards = Card::from_rows(rows);
-        let (memo, _) = solution_2(&cards, &cards, HashMap::new(), HashMap::new());
-        assert_eq!(30_usize, memo.values().into_iter().sum());
+        assert_eq!(vec![1, 2, 4, 8, 14, 1], copies_per_card(&cards));
+        assert_eq!(30, solution_2(&cards));
     }
 
     #[test]
@@ -179,10 +172,17 @@ mod tests {
         let input = get_input(file!(), "input1.txt");
         let rows = get_rows(input);
         let cards = Card::from_rows(rows);
-        let start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
-        let (memo, _) = solution_2(&cards, &cards, HashMap::new(), HashMap::new());
-        let end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
-        println!("{:?}", end - start);
-        assert_eq!(5539496_usize, memo.values().into_iter().sum());
+        assert_eq!(5539496, log_method_duration(|| solution_2(&cards)));
+    }
+
+    #[test]
+    fn copies_scale_to_long_cascades() {
+        let rows = (1..=200_000)
+            .map(|id| format!("Card {id}: 1 2 | 1 3"))
+            .collect();
+        let cards = Card::from_rows(rows);
+        let copies = copies_per_card(&cards);
+        assert_eq!(200_000, copies[199_999]);
+        assert_eq!(200_000 * 200_001 / 2, copies.iter().sum::<usize>());
     }
 }
@@ -1,4 +1,9 @@
-use std::ops::Range;
+use std::{fmt, ops::Range};
+
+use crate::{
+    shared::{answer::Answer, cancellation::checkpoint, file_parser::get_input, registry::DayPart},
+    trace,
+};
 
 use super::seeds::Seeds;
 
@@ -10,15 +15,6 @@ struct AlmenacRange {
 }
 
 impl AlmenacRange {
-    fn calculate_value_for_seed(option: Option<&Self>, input: usize) -> usize {
-        match option {
-            Some(mapping) => {
-                return mapping.get_value_for_seed(input);
-            }
-            None => input,
-        }
-    }
-
     fn get_value_for_seed(&self, input: usize) -> usize {
         input - self.source + self.destination
     }
@@ -32,10 +28,43 @@ impl AlmenacRange {
     }
 }
 
+#[derive(Debug, PartialEq)]
+enum AlmanacError {
+    OverlappingRanges {
+        map: String,
+        first: Range<usize>,
+        second: Range<usize>,
+    },
+    MissingMap(String),
+    Unreachable {
+        from: String,
+        to: String,
+    },
+}
+
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacError::OverlappingRanges { map, first, second } => write!(
+                f,
+                "{} map has overlapping sources {}-{} and {}-{}",
+                map, first.start, first.end, second.start, second.end
+            ),
+            AlmanacError::MissingMap(from) => write!(f, "no map starts at {}", from),
+            AlmanacError::Unreachable { from, to } => {
+                write!(f, "the maps from {} never reach {}", from, to)
+            }
+        }
+    }
+}
+
 struct AlmenacMap {
     from: String,
     to: String,
     mappings: Vec<AlmenacRange>,
+    // Stretches (end included) between the lowest and highest source that no
+    // range covers, numbers in there keep their value
+    gaps: Vec<Range<usize>>,
 }
 
 impl PartialEq for AlmenacMap {
@@ -45,12 +74,145 @@ impl PartialEq for AlmenacMap {
 }
 
 impl AlmenacMap {
-    fn get_corresponding_number(&self, input: usize) -> usize {
-        AlmenacRange::calculate_value_for_seed(self.get_matched_range(input), input)
+    // Sorts the mappings from high to low and refuses sources that overlap,
+    // as a number could then go to two places
+    fn new(
+        from: String,
+        to: String,
+        mut mappings: Vec<AlmenacRange>,
+    ) -> Result<Self, AlmanacError> {
+        mappings.sort_by(|m1, m2| m2.source.cmp(&m1.source));
+        let mut gaps = Vec::new();
+        for pair in mappings.windows(2) {
+            let (higher, lower) = (&pair[0], &pair[1]);
+            if lower.get_max() >= higher.get_min() {
+                return Err(AlmanacError::OverlappingRanges {
+                    map: format!("{}-to-{}", from, to),
+                    first: Range {
+                        start: lower.get_min(),
+                        end: lower.get_max(),
+                    },
+                    second: Range {
+                        start: higher.get_min(),
+                        end: higher.get_max(),
+                    },
+                });
+            }
+            if lower.get_max() + 1 < higher.get_min() {
+                gaps.push(Range {
+                    start: lower.get_max() + 1,
+                    end: higher.get_min() - 1,
+                });
+            }
+        }
+        gaps.reverse();
+        Ok(Self {
+            from,
+            to,
+            mappings,
+            gaps,
+        })
+    }
+
+    fn identity(category: &str) -> Self {
+        Self {
+            from: category.to_owned(),
+            to: category.to_owned(),
+            mappings: Vec::new(),
+            gaps: Vec::new(),
+        }
+    }
+
+    // Cuts 0..=usize::MAX into the source ranges and the stretches around
+    // them, from low to high, each with the number its start goes to
+    fn get_pieces(&self) -> Vec<(Range<usize>, usize)> {
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        for mapping in self.mappings.iter().rev() {
+            if mapping.get_min() > start {
+                pieces.push((
+                    Range {
+                        start,
+                        end: mapping.get_min() - 1,
+                    },
+                    start,
+                ));
+            }
+            pieces.push((
+                Range {
+                    start: mapping.get_min(),
+                    end: mapping.get_max(),
+                },
+                mapping.destination,
+            ));
+            match mapping.get_max().checked_add(1) {
+                Some(next) => start = next,
+                None => return pieces,
+            }
+        }
+        pieces.push((
+            Range {
+                start,
+                end: usize::MAX,
+            },
+            start,
+        ));
+        pieces
+    }
+
+    // A single map doing what this map and then `next` do
+    fn then(&self, next: &AlmenacMap) -> AlmenacMap {
+        assert_eq!(self.to, next.from, "maps do not follow each other");
+        let mut mappings: Vec<AlmenacRange> = Vec::new();
+        for (source, destination) in self.get_pieces() {
+            checkpoint();
+            let image = Range {
+                start: destination,
+                end: destination + (source.end - source.start),
+            };
+            // the parts come out in the same order as the numbers going in
+            let mut start = source.start;
+            for part in next.get_ranges_inside_map(image) {
+                let end = start + (part.end - part.start);
+                if part.start != start {
+                    match mappings.last_mut() {
+                        Some(last)
+                            if last.source + last.length == start
+                                && last.destination + last.length == part.start =>
+                        {
+                            last.length += end - start + 1;
+                        }
+                        _ => mappings.push(AlmenacRange {
+                            source: start,
+                            destination: part.start,
+                            length: end - start + 1,
+                        }),
+                    }
+                }
+                start = end.saturating_add(1);
+            }
+        }
+        AlmenacMap::new(self.from.clone(), next.to.clone(), mappings)
+            .expect("the pieces of a map never overlap")
+    }
+
+    // Every number that ends up at `output`, a map is not always one to one
+    fn get_source_numbers(&self, output: usize) -> Vec<usize> {
+        let mut sources: Vec<usize> = self
+            .mappings
+            .iter()
+            .filter(|m| m.destination <= output && output - m.destination < m.length)
+            .map(|m| output - m.destination + m.source)
+            .collect();
+        if self.get_matched_range(output).is_none() {
+            sources.push(output);
+        }
+        sources.sort();
+        sources
     }
 
     fn get_matched_range(&self, input: usize) -> Option<&AlmenacRange> {
-        if self.mappings.last().unwrap().get_min() > input {
+        if self.mappings.last().is_none_or(|m| m.get_min() > input) {
             return None;
         }
         for mapping in &self.mappings {
@@ -61,54 +223,58 @@ impl AlmenacMap {
         None
     }
 
+    // Splits the seeds (end included) into the parts that are moved by a mapping
+    // and the parts in between that keep their number
     fn get_ranges_inside_map(&self, seeds: Range<usize>) -> Seeds {
         let mut ranges = Seeds::new();
-        for mapping in &self.mappings {
-            let mut start = seeds.start;
-            let mut end = seeds.end;
-            if mapping.get_min() >= seeds.end || mapping.get_max() <= seeds.start {
+        let mut start = seeds.start;
+        // mappings are sorted from high to low
+        for mapping in self.mappings.iter().rev() {
+            if mapping.get_max() < start {
                 continue;
             }
-            if mapping.get_min() > seeds.start {
-                start = mapping.get_min();
+            if mapping.get_min() > seeds.end {
+                break;
             }
-            if mapping.get_max() < seeds.end {
-                end = mapping.get_max();
+            if mapping.get_min() > start {
+                ranges.push(Range {
+                    start,
+                    end: mapping.get_min() - 1,
+                });
+                start = mapping.get_min();
             }
-
+            let end = mapping.get_max().min(seeds.end);
             ranges.push(Range {
                 start: mapping.get_value_for_seed(start),
                 end: mapping.get_value_for_seed(end),
             });
+            if end == seeds.end {
+                return ranges;
+            }
+            start = end + 1;
         }
-        if ranges.is_empty() {
-            ranges.push(seeds);
-        }
+        ranges.push(Range {
+            start,
+            end: seeds.end,
+        });
         ranges
     }
 
     fn get_corresponding_ranges(&self, seeds: Seeds) -> Seeds {
         let mut new_seeds = Seeds::new();
         for seed in seeds {
-            let start_mapping_match = self.get_matched_range(seed.start);
-            let end_mapping_match = self.get_matched_range(seed.end);
-            if start_mapping_match == end_mapping_match {
-                new_seeds.push(Range {
-                    start: AlmenacRange::calculate_value_for_seed(start_mapping_match, seed.start),
-                    end: AlmenacRange::calculate_value_for_seed(end_mapping_match, seed.end),
-                });
-            } else {
-                for test in self.get_ranges_inside_map(seed) {
-                    new_seeds.push(test);
-                }
+            for range in self.get_ranges_inside_map(seed) {
+                new_seeds.push(range);
             }
         }
         new_seeds
     }
 }
 
-impl From<&str> for AlmenacMap {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for AlmenacMap {
+    type Error = AlmanacError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         let mut from = String::new();
         let mut to = String::new();
         let mut mappings = Vec::new();
@@ -127,8 +293,7 @@ impl From<&str> for AlmenacMap {
             }
             mappings.push(AlmenacRange::from(row));
         }
-        mappings.sort_by(|m1, m2| m2.source.cmp(&m1.source));
-        Self { from, to, mappings }
+        AlmenacMap::new(from, to, mappings)
     }
 }
 
@@ -162,58 +327,187 @@ impl From<String> for Game {
             if map_as_str.is_empty() {
                 continue;
             }
-            maps.push(AlmenacMap::from(map_as_str));
+            let map = AlmenacMap::try_from(map_as_str).unwrap_or_else(|error| panic!("{}", error));
+            if !map.gaps.is_empty() {
+                trace!(
+                    Debug,
+                    "{}-to-{} map keeps the numbers of {}",
+                    map.from,
+                    map.to,
+                    map.gaps
+                        .iter()
+                        .map(|gap| format!("{}-{}", gap.start, gap.end))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                );
+            }
+            maps.push(map);
         }
         Self { seeds, maps }
     }
 }
 
 impl Game {
-    fn get_corresponding_ranges(
-        &self,
-        input: Range<usize>,
-        start: &str,
-        destination: &str,
-    ) -> Seeds {
-        let mut last_to = start;
-        let mut corresponding_ranges = Seeds::new();
-        corresponding_ranges.push(input);
-        while last_to.ne(destination) {
-            (last_to, corresponding_ranges) = self.get_next(last_to, corresponding_ranges);
+    // Folds the maps on the way from `start` to `destination` into one
+    fn compose(&self, start: &str, destination: &str) -> Result<AlmenacMap, AlmanacError> {
+        let mut composed = AlmenacMap::identity(start);
+        let mut steps = 0;
+        while composed.to != destination {
+            // every map is needed at most once, more steps means going round
+            if steps == self.maps.len() {
+                return Err(AlmanacError::Unreachable {
+                    from: start.to_owned(),
+                    to: destination.to_owned(),
+                });
+            }
+            let next = self
+                .maps
+                .iter()
+                .find(|m| m.from == composed.to)
+                .ok_or_else(|| AlmanacError::MissingMap(composed.to.clone()))?;
+            composed = composed.then(next);
+            steps += 1;
         }
-        corresponding_ranges
+        Ok(composed)
     }
 
-    fn get_next(&self, start: &str, corresponding_ranges: Seeds) -> (&str, Seeds) {
-        let corresponding_map = self.maps.iter().find(|m| m.from.eq(start)).unwrap();
-        (
-            &corresponding_map.to,
-            corresponding_map.get_corresponding_ranges(corresponding_ranges),
-        )
+    fn get_source_numbers(
+        &self,
+        start: &str,
+        destination: &str,
+        output: usize,
+    ) -> Result<Vec<usize>, AlmanacError> {
+        Ok(self.compose(start, destination)?.get_source_numbers(output))
     }
 }
 
 fn solution(game: &Game, start: String, destination: String) -> usize {
-    game.seeds
-        .clone()
+    let almanac = game
+        .compose(&start, &destination)
+        .unwrap_or_else(|error| panic!("{}", error));
+    let lowest = almanac
+        .get_corresponding_ranges(Seeds::from(game.seeds.to_vec()))
         .into_iter()
-        .map(|seed| {
-            game.get_corresponding_ranges(seed, &start, &destination)
-                .into_iter()
-                .map(|r| r.start)
-                .min()
-                .unwrap()
-        })
+        .map(|r| r.start)
         .min()
-        .unwrap()
+        .unwrap();
+    trace!(
+        Debug,
+        "{} {} comes from {} {:?}",
+        destination,
+        lowest,
+        start,
+        game.get_source_numbers(&start, &destination, lowest)
+            .unwrap_or_default()
+    );
+    lowest
+}
+
+pub fn parts() -> Vec<DayPart> {
+    let solve = |file_name| {
+        let game = Game::from(get_input(file!(), file_name));
+        Answer::from(solution(
+            &game,
+            String::from("seed"),
+            String::from("location"),
+        ))
+    };
+    vec![
+        DayPart::new(2023, 5, 1, move || solve("input1.txt")).expecting(31599214),
+        DayPart::new(2023, 5, 2, move || solve("input2.txt")).expecting(20358599),
+    ]
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::shared::{file_parser::get_input, method_duration::log_method_duration};
+    use crate::shared::{
+        cancellation::{configured_budget, with_time_budget},
+        differential::Differential,
+        file_parser::get_input,
+        method_duration::log_method_duration,
+        random::{InputGenerator, Rng},
+    };
 
     use super::*;
 
+    // The number a single seed is moved to, the reference for the range
+    // arithmetic of the solutions
+    impl AlmenacMap {
+        fn get_corresponding_number(&self, input: usize) -> usize {
+            match self.get_matched_range(input) {
+                Some(mapping) => mapping.get_value_for_seed(input),
+                None => input,
+            }
+        }
+    }
+
+    const ALMANAC_CATEGORIES: [&str; 8] = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+
+    // Every map cuts 0..max_value into pieces and moves some of them to a random
+    // destination, the source ranges of a map never overlap
+    struct AlmanacGenerator {
+        seed_ranges: usize,
+        max_seed_length: usize,
+        ranges_per_map: usize,
+        max_value: usize,
+    }
+
+    impl Default for AlmanacGenerator {
+        fn default() -> Self {
+            Self {
+                seed_ranges: 10,
+                max_seed_length: 400_000_000,
+                ranges_per_map: 30,
+                max_value: 4_000_000_000,
+            }
+        }
+    }
+
+    impl InputGenerator for AlmanacGenerator {
+        fn generate(&self, rng: &mut Rng) -> String {
+            let seeds: Vec<String> = (0..self.seed_ranges)
+                .map(|_| {
+                    let length = rng.range(1..self.max_seed_length + 1);
+                    let start = rng.range(0..self.max_value - length.min(self.max_value - 1));
+                    format!("{} {}", start, length)
+                })
+                .collect();
+            let mut almanac = format!("seeds: {}\n", seeds.join(" "));
+            for names in ALMANAC_CATEGORIES.windows(2) {
+                let mut cuts: Vec<usize> = (0..self.ranges_per_map * 2)
+                    .map(|_| rng.range(0..self.max_value))
+                    .chain([0, self.max_value])
+                    .collect();
+                cuts.sort();
+                cuts.dedup();
+                almanac.push_str(&format!("\n{}-to-{} map:\n", names[0], names[1]));
+                let pieces = cuts.len() - 1;
+                let mut mapped = 0;
+                for (index, piece) in cuts.windows(2).enumerate() {
+                    // a map always has at least one range
+                    let last_chance = index == pieces - 1 && mapped == 0;
+                    if rng.chance(1, 3) && !last_chance {
+                        continue;
+                    }
+                    mapped += 1;
+                    let length = piece[1] - piece[0];
+                    let destination = rng.range(0..self.max_value - length + 1);
+                    almanac.push_str(&format!("{} {} {}\n", destination, piece[0], length));
+                }
+            }
+            almanac
+        }
+    }
+
     #[test]
     fn seeds_from_string() {
         let seeds_as_str = "seeds: 79 14 55 13";
@@ -228,7 +522,7 @@ mod tests {
     #[test]
     fn map_from_string() {
         let map_as_str = "soil-to-fertilizer map:\n0 15 37\n37 52 2\n39 0 15";
-        let almenac_map = AlmenacMap::from(map_as_str);
+        let almenac_map = AlmenacMap::try_from(map_as_str).unwrap();
         assert_eq!("soil", &almenac_map.from);
         assert_eq!("fertilizer", &almenac_map.to);
         assert_eq!(&0, &almenac_map.mappings.get(1).unwrap().destination);
@@ -247,7 +541,7 @@ mod tests {
     #[test]
     fn test_get_corresponding_ranges() {
         let map_as_str = "soil-to-fertilizer map:\n0 15 37\n37 52 2\n39 0 15";
-        let almenac_map = AlmenacMap::from(map_as_str);
+        let almenac_map = AlmenacMap::try_from(map_as_str).unwrap();
         almenac_map.test_corresponding_range_single_value(12, 51);
         almenac_map.test_corresponding_range_single_value(14, 53);
         almenac_map.test_corresponding_range_single_value(15, 0);
@@ -271,10 +565,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ranges_split_at_every_mapping() {
+        // 40-49 is not moved by any mapping and used to get lost
+        let game = Game::from(String::from(
+            "seeds: 40 60\n\nseed-to-soil map:\n50 98 2\n52 50 48",
+        ));
+        let ranges =
+            game.maps[0].get_corresponding_ranges(Seeds::from(vec![Range { start: 40, end: 99 }]));
+        assert_eq!(
+            vec![
+                Range { start: 40, end: 49 },
+                Range { start: 52, end: 99 },
+                Range { start: 50, end: 51 },
+            ],
+            *ranges
+        );
+    }
+
     #[test]
     fn soil_to_fertilizer_corresponding_number() {
         let map_as_str = "seed-to-soil map:\n50 98 2\n52 50 48";
-        let almenac_map = AlmenacMap::from(map_as_str);
+        let almenac_map = AlmenacMap::try_from(map_as_str).unwrap();
         assert_eq!(13, AlmenacMap::get_corresponding_number(&almenac_map, 13));
         assert_eq!(14, AlmenacMap::get_corresponding_number(&almenac_map, 14));
         assert_eq!(81, AlmenacMap::get_corresponding_number(&almenac_map, 79));
@@ -284,6 +596,89 @@ mod tests {
         assert_eq!(51, AlmenacMap::get_corresponding_number(&almenac_map, 99));
     }
 
+    #[test]
+    fn overlapping_sources_are_refused() {
+        let map_as_str = "seed-to-soil map:\n50 98 2\n52 90 10";
+        assert_eq!(
+            Err(AlmanacError::OverlappingRanges {
+                map: String::from("seed-to-soil"),
+                first: Range { start: 90, end: 99 },
+                second: Range { start: 98, end: 99 },
+            }),
+            AlmenacMap::try_from(map_as_str).map(|map| map.gaps)
+        );
+    }
+
+    #[test]
+    fn gaps_between_sources() {
+        let map_as_str = "seed-to-soil map:\n0 30 5\n40 10 10\n90 0 4";
+        let almenac_map = AlmenacMap::try_from(map_as_str).unwrap();
+        assert_eq!(
+            vec![Range { start: 4, end: 9 }, Range { start: 20, end: 29 }],
+            almenac_map.gaps
+        );
+    }
+
+    #[test]
+    fn composed_map_matches_walking_the_maps() {
+        let game = Game::from(get_input(file!(), "example1.txt"));
+        let almanac = game.compose("seed", "location").unwrap();
+        assert_eq!("seed", almanac.from);
+        assert_eq!("location", almanac.to);
+        for seed in 0..120 {
+            let walked = game
+                .maps
+                .iter()
+                .fold(seed, |value, map| map.get_corresponding_number(value));
+            assert_eq!(walked, almanac.get_corresponding_number(seed), "{}", seed);
+        }
+        let soil_to_light = game.compose("soil", "light").unwrap();
+        assert_eq!(
+            game.maps[3].get_corresponding_number(
+                game.maps[2].get_corresponding_number(game.maps[1].get_corresponding_number(60))
+            ),
+            soil_to_light.get_corresponding_number(60)
+        );
+    }
+
+    #[test]
+    fn location_to_seed() {
+        let game = Game::from(get_input(file!(), "example1.txt"));
+        assert_eq!(
+            Ok(vec![13]),
+            game.get_source_numbers("seed", "location", 35)
+        );
+        let almanac = game.compose("seed", "location").unwrap();
+        for location in 0..120 {
+            for seed in almanac.get_source_numbers(location) {
+                assert_eq!(location, almanac.get_corresponding_number(seed));
+            }
+        }
+    }
+
+    #[test]
+    fn compose_without_a_way() {
+        let game = Game::from(get_input(file!(), "example1.txt"));
+        assert_eq!(
+            Err(AlmanacError::MissingMap(String::from("location"))),
+            game.compose("soil", "seed").map(|map| map.to)
+        );
+        assert_eq!(
+            Err(AlmanacError::MissingMap(String::from("rock"))),
+            game.compose("rock", "seed").map(|map| map.to)
+        );
+        let round = Game::from(String::from(
+            "seeds: 1 1\n\na-to-b map:\n5 0 2\n\nb-to-a map:\n0 5 2\n",
+        ));
+        assert_eq!(
+            Err(AlmanacError::Unreachable {
+                from: String::from("a"),
+                to: String::from("c"),
+            }),
+            round.compose("a", "c").map(|map| map.to)
+        );
+    }
+
     #[test]
     fn example_1_test() {
         let input = get_input(file!(), "example1.txt");
@@ -318,8 +713,61 @@ mod tests {
     fn solution_2_test() {
         let input = get_input(file!(), "input2.txt");
         let game = Game::from(input);
-        let solution =
-            log_method_duration(|| solution(&game, String::from("seed"), String::from("location")));
-        assert_eq!(20358599, solution);
+        let solution = with_time_budget(configured_budget(), || {
+            log_method_duration(|| solution(&game, String::from("seed"), String::from("location")))
+        });
+        assert_eq!(Ok(20358599), solution);
+    }
+
+    // Looks up every single seed, only usable on small almanacs
+    fn brute_force_lowest_location(game: &Game) -> usize {
+        game.seeds
+            .iter()
+            .flat_map(|seeds| seeds.start..=seeds.end)
+            .map(|seed| {
+                game.maps
+                    .iter()
+                    .fold(seed, |value, map| map.get_corresponding_number(value))
+            })
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn differential_generated_almanacs() {
+        let generator = AlmanacGenerator {
+            seed_ranges: 4,
+            max_seed_length: 20,
+            ranges_per_map: 4,
+            max_value: 100,
+        };
+        let differential = Differential::new(
+            "solution",
+            |input: &String| {
+                solution(
+                    &Game::from(input.clone()),
+                    String::from("seed"),
+                    String::from("location"),
+                )
+            },
+            "brute_force_lowest_location",
+            |input: &String| brute_force_lowest_location(&Game::from(input.clone())),
+        );
+        let result = differential.check_random(5, 500, |rng| generator.generate(rng));
+        if let Err(disagreement) = result {
+            panic!("{}", disagreement);
+        }
+    }
+
+    #[test]
+    #[ignore = "stress test, run with --ignored --nocapture"]
+    fn stress_generated_almanac() {
+        let generator = AlmanacGenerator {
+            seed_ranges: 1_000,
+            ranges_per_map: 1_000,
+            ..Default::default()
+        };
+        let game = Game::from(generator.generate_with_seed(5));
+        log_method_duration(|| solution(&game, String::from("seed"), String::from("location")));
     }
 }
@@ -1,71 +1,92 @@
-use std::ops::{AddAssign, MulAssign};
+use std::ops::{MulAssign, RangeInclusive};
+
+use crate::shared::{
+    answer::Answer,
+    file_parser::{get_input, get_rows},
+    registry::DayPart,
+};
+
+// Writes the numbers after each other, None once that no longer fits
+fn concatenate(mut numbers: impl Iterator<Item = u64>) -> Option<u64> {
+    numbers.try_fold(0u64, |joined, number| {
+        let shift = 10u64.checked_pow(number.checked_ilog10().unwrap_or(0) + 1)?;
+        joined.checked_mul(shift)?.checked_add(number)
+    })
+}
 
 struct Race {
-    race_duration_in_ms: usize,
-    distance_record: usize,
+    race_duration_in_ms: u64,
+    distance_record: u64,
 }
 
 impl Race {
-    fn calculate_optimal_button_press_time(&self) -> usize {
-        self.race_duration_in_ms / 2
+    // Reads the races as one race, as if the spaces between them were kerning
+    fn concatenated(races: &[Race]) -> Option<Race> {
+        Some(Race {
+            race_duration_in_ms: concatenate(races.iter().map(|r| r.race_duration_in_ms))?,
+            distance_record: concatenate(races.iter().map(|r| r.distance_record))?,
+        })
     }
 
-    fn calculate_distance(&self, button_press_duration: usize) -> usize {
-        let boat_speed = button_press_duration;
-        let time_left = self.race_duration_in_ms - button_press_duration;
-        let distance_traveled = boat_speed * time_left;
-        distance_traveled
+    fn calculate_distance(&self, button_press_duration: u64) -> u128 {
+        let boat_speed = button_press_duration as u128;
+        let time_left = (self.race_duration_in_ms - button_press_duration) as u128;
+        boat_speed * time_left
     }
 
-    // could be optimized by taking into account the sqrt(distance_record) as a
-    // starting point for traversing lower
-    fn nr_of_better_solutions_than_record(&self) -> usize {
-        let mut nr_of_better_solutions = 0;
-        let optimal_button_press = self.calculate_optimal_button_press_time();
-        let drive_duration = self.race_duration_in_ms - optimal_button_press;
-        let mut current_solution = (optimal_button_press, drive_duration);
-        nr_of_better_solutions.add_assign(1);
-        if current_solution.0 != current_solution.1 {
-            nr_of_better_solutions.add_assign(1);
-        }
-        let mut should_continue = true;
-        while should_continue {
-            current_solution = (current_solution.0 - 1, current_solution.1 + 1);
-            if self.distance_record < self.calculate_distance(current_solution.0) {
-                nr_of_better_solutions.add_assign(2);
-            } else {
-                should_continue = false;
-            }
-        }
-        nr_of_better_solutions
+    fn beats_record(&self, button_press_duration: u64) -> bool {
+        self.calculate_distance(button_press_duration) > self.distance_record as u128
     }
 
-    fn calculate_better_solutions_than_record(&self) -> Vec<(usize, usize)> {
-        let mut better_solutions = Vec::new();
-        let optimal_button_press = self.calculate_optimal_button_press_time();
-        let drive_duration = self.race_duration_in_ms - optimal_button_press;
-        let mut current_solution = (optimal_button_press, drive_duration);
-        better_solutions.push(current_solution);
-        if current_solution.0 != current_solution.1 {
-            better_solutions.push((current_solution.1, current_solution.0));
+    // The presses p with p * (duration - p) > record lie strictly between the
+    // roots (duration -+ sqrt(duration² - 4 * record)) / 2. Everything fits in a
+    // u128, the integer square root only rounds down, so the first guess is
+    // at most one off and checked against the distance itself, which also
+    // decides ties with the record.
+    fn winning_button_presses(&self) -> Option<RangeInclusive<u64>> {
+        let duration = self.race_duration_in_ms as u128;
+        let discriminant = (duration * duration).checked_sub(4 * self.distance_record as u128)?;
+        let mut lowest = ((duration - discriminant.isqrt()) / 2) as u64;
+        while lowest > 0 && self.beats_record(lowest - 1) {
+            lowest -= 1;
         }
-        let mut should_continue = true;
-        while should_continue {
-            current_solution = (current_solution.0 - 1, current_solution.1 + 1);
-            if self.distance_record < self.calculate_distance(current_solution.0) {
-                better_solutions.push(current_solution);
-                better_solutions.push((current_solution.1, current_solution.0));
-            } else {
-                should_continue = false;
-            }
+        while lowest <= self.race_duration_in_ms / 2 && !self.beats_record(lowest) {
+            lowest += 1;
         }
-        better_solutions
+        if lowest > self.race_duration_in_ms / 2 {
+            return None;
+        }
+        // the distances are symmetric around half the duration
+        Some(lowest..=self.race_duration_in_ms - lowest)
+    }
+
+    fn nr_of_better_solutions_than_record(&self) -> u64 {
+        self.winning_button_presses()
+            .map_or(0, |presses| presses.end() - presses.start() + 1)
     }
 }
 
 type Records = Vec<Race>;
 
-fn solution_1(records: Records) -> usize {
+// The races are the columns of the `Time:` and `Distance:` rows
+fn parse_races(rows: Vec<String>) -> Records {
+    let numbers = |row: &str| -> Vec<u64> {
+        row.split_whitespace()
+            .skip(1)
+            .map(|number| number.parse().unwrap())
+            .collect()
+    };
+    numbers(&rows[0])
+        .into_iter()
+        .zip(numbers(&rows[1]))
+        .map(|(race_duration_in_ms, distance_record)| Race {
+            race_duration_in_ms,
+            distance_record,
+        })
+        .collect()
+}
+
+fn solution_1(records: Records) -> u64 {
     let mut result = 1;
     for race in records {
         result.mul_assign(race.nr_of_better_solutions_than_record());
@@ -73,9 +94,25 @@ fn solution_1(records: Records) -> usize {
     result
 }
 
+// Part two reads the races as one race
+pub fn parts() -> Vec<DayPart> {
+    let races = || parse_races(get_rows(get_input(file!(), "input1.txt")));
+    vec![
+        DayPart::new(2023, 6, 1, move || Answer::from(solution_1(races()))).expecting(316800),
+        DayPart::new(2023, 6, 2, move || {
+            let race = Race::concatenated(&races()).unwrap();
+            Answer::from(solution_1(vec![race]))
+        })
+        .expecting(45647654),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::shared::method_duration::log_method_duration;
+    use crate::shared::{
+        file_parser::{get_input, get_rows},
+        method_duration::log_method_duration,
+    };
 
     use super::*;
 
@@ -114,9 +151,10 @@ mod tests {
         let race1 = input.get(0).unwrap();
         let race2 = input.get(1).unwrap();
         let race3 = input.get(2).unwrap();
-        assert_eq!(4, race1.calculate_better_solutions_than_record().len());
-        assert_eq!(8, race2.calculate_better_solutions_than_record().len());
-        assert_eq!(9, race3.calculate_better_solutions_than_record().len());
+        assert_eq!(Some(2..=5), race1.winning_button_presses());
+        assert_eq!(Some(4..=11), race2.winning_button_presses());
+        // 10 and 20 exactly tie the record
+        assert_eq!(Some(11..=19), race3.winning_button_presses());
         assert_eq!(288, solution_1(input));
     }
 
@@ -143,6 +181,20 @@ mod tests {
         assert_eq!(316800, solution_1(input));
     }
 
+    #[test]
+    fn parse_assets() {
+        let example = parse_races(get_rows(get_input(file!(), "example1.txt")));
+        assert_eq!(288, solution_1(example));
+        let input = parse_races(get_rows(get_input(file!(), "input1.txt")));
+        assert_eq!(
+            vec![(61, 430), (67, 1036), (75, 1307), (71, 1150)],
+            input
+                .iter()
+                .map(|race| (race.race_duration_in_ms, race.distance_record))
+                .collect::<Vec<_>>()
+        );
+    }
+
     #[test]
     fn example_2_test() {
         let input: Records = vec![Race {
@@ -165,4 +217,88 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn concatenated_example() {
+        let races = vec![
+            Race {
+                race_duration_in_ms: 7,
+                distance_record: 9,
+            },
+            Race {
+                race_duration_in_ms: 15,
+                distance_record: 40,
+            },
+            Race {
+                race_duration_in_ms: 30,
+                distance_record: 200,
+            },
+        ];
+        let race = Race::concatenated(&races).unwrap();
+        assert_eq!(71530, race.race_duration_in_ms);
+        assert_eq!(940200, race.distance_record);
+        let too_long = Race {
+            race_duration_in_ms: u64::MAX,
+            distance_record: 1,
+        };
+        assert!(Race::concatenated(&[too_long, Race::concatenated(&races).unwrap()]).is_none());
+    }
+
+    #[test]
+    fn closed_form_matches_every_press() {
+        for race_duration_in_ms in 0..60 {
+            let best = (race_duration_in_ms / 2) * (race_duration_in_ms - race_duration_in_ms / 2);
+            for distance_record in 0..best + 3 {
+                let race = Race {
+                    race_duration_in_ms,
+                    distance_record,
+                };
+                let winning: Vec<u64> = (0..=race_duration_in_ms)
+                    .filter(|press| race.beats_record(*press))
+                    .collect();
+                let expected = winning
+                    .first()
+                    .map(|first| *first..=*winning.last().unwrap());
+                assert_eq!(
+                    expected,
+                    race.winning_button_presses(),
+                    "{} {}",
+                    race_duration_in_ms,
+                    distance_record
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn huge_races() {
+        let race = Race {
+            race_duration_in_ms: u64::MAX,
+            distance_record: 0,
+        };
+        assert_eq!(Some(1..=u64::MAX - 1), race.winning_button_presses());
+        let half = u64::MAX / 2;
+        let race = Race {
+            race_duration_in_ms: half * 2,
+            distance_record: u64::MAX,
+        };
+        let presses = race.winning_button_presses().unwrap();
+        assert!(race.beats_record(*presses.start()));
+        assert!(!race.beats_record(presses.start() - 1));
+        assert_eq!(half * 2 - presses.start(), *presses.end());
+        // only the very top of the curve is left
+        let square = Race {
+            race_duration_in_ms: 2 * 4_000_000_000,
+            distance_record: 4_000_000_000 * 4_000_000_000 - 1,
+        };
+        assert_eq!(
+            Some(4_000_000_000..=4_000_000_000),
+            square.winning_button_presses()
+        );
+        let tie = Race {
+            distance_record: 4_000_000_000 * 4_000_000_000,
+            ..square
+        };
+        assert_eq!(None, tie.winning_button_presses());
+    }
 }
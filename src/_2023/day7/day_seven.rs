@@ -1,180 +1,184 @@
-use std::collections::HashSet;
-
-#[derive(Debug, Eq, PartialEq, PartialOrd, Ord)]
-enum HandValue {
-    HighCard,
-    Pair,
-    TwoPair,
-    ThreeOfAKind,
-    FullHouse,
-    FourOfAKind,
-    FiveOfAKind,
-}
+use std::{collections::HashMap, fmt};
 
-struct Play {
-    bid: usize,
-    hand_value: HandValue,
-    hand: Vec<usize>,
-}
+use crate::{
+    shared::{
+        answer::Answer,
+        file_parser::{get_input, get_rows},
+        registry::DayPart,
+    },
+    trace,
+};
 
-impl Play {
-    fn from_2(value: &str) -> Self {
-        let mut hand = Vec::new();
-        let mut parts = value.split(" ");
-        for char in parts.next().unwrap().chars().into_iter() {
-            hand.push(card_value_2(char));
-        }
+#[derive(Debug, PartialEq)]
+enum PlayError {
+    UnknownCard(char),
+    WrongHandSize { expected: usize, found: usize },
+    NoCategory(String),
+    InvalidBid(String),
+}
 
-        let hand_value = hand_value_2(&hand);
-        let bid = parts.next().unwrap().parse().unwrap();
-        Self {
-            hand,
-            hand_value,
-            bid,
+impl fmt::Display for PlayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayError::UnknownCard(card) => write!(f, "{} is not a card", card),
+            PlayError::WrongHandSize { expected, found } => {
+                write!(f, "a hand has {} cards, not {}", expected, found)
+            }
+            PlayError::NoCategory(hand) => write!(f, "{} fits no category", hand),
+            PlayError::InvalidBid(row) => write!(f, "no bid in {}", row),
         }
     }
 }
 
-impl From<&str> for Play {
-    fn from(value: &str) -> Self {
-        let mut hand = Vec::new();
-        let mut parts = value.split(" ");
-        for char in parts.next().unwrap().chars().into_iter() {
-            hand.push(card_value(char));
-        }
-
-        let hand_value = hand_value_1(&hand);
-        let bid = parts.next().unwrap().parse().unwrap();
-        Self {
-            hand,
-            hand_value,
-            bid,
-        }
-    }
+// A kind of hand, described by the groups of equal cards it needs
+struct Category {
+    name: &'static str,
+    groups: Vec<usize>,
 }
 
-fn hand_value_1(hand: &Vec<usize>) -> HandValue {
-    let test = hand
-        .into_iter()
-        .collect::<HashSet<&usize>>()
-        .into_iter()
-        .collect::<Vec<&usize>>();
-    if test.len() == 5 {
-        return HandValue::HighCard;
-    }
-    if test.len() == 4 {
-        return HandValue::Pair;
+impl Category {
+    fn new(name: &'static str, groups: &[usize]) -> Self {
+        let mut groups = groups.to_vec();
+        groups.sort_by(|g1, g2| g2.cmp(g1));
+        Self { name, groups }
     }
-    if test.len() == 3 {
-        if hand.iter().filter(|hv| hv.eq(test.get(0).unwrap())).count() == 2
-            || hand.iter().filter(|hv| hv.eq(test.get(1).unwrap())).count() == 2
-            || hand.iter().filter(|hv| hv.eq(test.get(2).unwrap())).count() == 2
-        {
-            return HandValue::TwoPair;
-        }
-        return HandValue::ThreeOfAKind;
+
+    // Wildcards needed on top of the hand's own groups (largest first) to
+    // have this category. Putting the largest groups against the largest
+    // needs never wastes a wildcard.
+    fn wildcards_needed(&self, groups: &[usize]) -> usize {
+        self.groups
+            .iter()
+            .enumerate()
+            .map(|(index, needed)| needed.saturating_sub(*groups.get(index).unwrap_or(&0)))
+            .sum()
     }
-    if test.len() == 2 {
-        let first_value_freq = hand.iter().filter(|hv| hv.eq(test.get(0).unwrap())).count();
-        if first_value_freq == 2 || first_value_freq == 3 {
-            return HandValue::FullHouse;
-        } else {
-            return HandValue::FourOfAKind;
+}
+
+fn poker_categories() -> Vec<Category> {
+    vec![
+        Category::new("high card", &[1]),
+        Category::new("one pair", &[2]),
+        Category::new("two pair", &[2, 2]),
+        Category::new("three of a kind", &[3]),
+        Category::new("full house", &[3, 2]),
+        Category::new("four of a kind", &[4]),
+        Category::new("five of a kind", &[5]),
+    ]
+}
+
+// Everything that decides how plays rank
+struct Rules {
+    // weakest card first
+    card_order: Vec<char>,
+    // cards that count as whatever card makes the hand strongest
+    wildcards: Vec<char>,
+    hand_size: usize,
+    // weakest category first
+    categories: Vec<Category>,
+}
+
+impl Rules {
+    fn camel_cards() -> Self {
+        Self {
+            card_order: "23456789TJQKA".chars().collect(),
+            wildcards: Vec::new(),
+            hand_size: 5,
+            categories: poker_categories(),
         }
     }
-    HandValue::FiveOfAKind
-}
 
-fn hand_value_2(hand: &Vec<usize>) -> HandValue {
-    let mut test = hand.into_iter().collect::<HashSet<&usize>>();
-    test.remove(&1usize);
-    let test = test.into_iter().collect::<Vec<&usize>>();
-    let nr_of_jacks = hand.iter().filter(|hv| hv.eq(&&1usize)).count();
-    if test.len() == 5 {
-        return HandValue::HighCard;
+    // Jokers complete any category but are the weakest card on their own
+    fn with_wildcards(mut self, wildcards: &str) -> Self {
+        self.card_order.retain(|card| !wildcards.contains(*card));
+        self.card_order.splice(0..0, wildcards.chars());
+        self.wildcards = wildcards.chars().collect();
+        self
     }
-    if test.len() == 4 {
-        return HandValue::Pair;
+
+    fn jokers() -> Self {
+        Self::camel_cards().with_wildcards("J")
     }
-    if test.len() == 3 {
-        if nr_of_jacks == 0
-            && (hand.iter().filter(|hv| hv.eq(test.get(0).unwrap())).count() == 2
-                || hand.iter().filter(|hv| hv.eq(test.get(1).unwrap())).count() == 2
-                || hand.iter().filter(|hv| hv.eq(test.get(2).unwrap())).count() == 2)
-        {
-            return HandValue::TwoPair;
-        }
-        return HandValue::ThreeOfAKind;
+
+    fn rank(&self, card: char) -> Result<usize, PlayError> {
+        self.card_order
+            .iter()
+            .position(|c| *c == card)
+            .ok_or(PlayError::UnknownCard(card))
     }
-    if test.len() == 2 {
-        let check_freq = |freq: usize| {
-            if nr_of_jacks > 2 {
-                return false;
+
+    // Index of the strongest category the hand can make
+    fn category(&self, hand: &str) -> Result<usize, PlayError> {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        let mut wildcards = 0;
+        for card in hand.chars() {
+            if self.wildcards.contains(&card) {
+                wildcards += 1;
+            } else {
+                *counts.entry(card).or_default() += 1;
             }
-            return freq == 3 - nr_of_jacks || freq == 2 - nr_of_jacks;
-        };
-        let first_value_freq = hand.iter().filter(|hv| hv.eq(test.get(0).unwrap())).count();
-        let second_value_freq = hand.iter().filter(|hv| hv.eq(test.get(1).unwrap())).count();
-        if check_freq(first_value_freq) && check_freq(second_value_freq) {
-            return HandValue::FullHouse;
-        } else {
-            return HandValue::FourOfAKind;
         }
+        let mut groups: Vec<usize> = counts.into_values().collect();
+        groups.sort_by(|g1, g2| g2.cmp(g1));
+        self.categories
+            .iter()
+            .rposition(|category| category.wildcards_needed(&groups) <= wildcards)
+            .ok_or_else(|| PlayError::NoCategory(hand.to_owned()))
     }
-    HandValue::FiveOfAKind
-}
 
-fn card_value(value: char) -> usize {
-    match value.into() {
-        '2' => 2,
-        '3' => 3,
-        '4' => 4,
-        '5' => 5,
-        '6' => 6,
-        '7' => 7,
-        '8' => 8,
-        '9' => 9,
-        'T' => 10,
-        'J' => 11,
-        'Q' => 12,
-        'K' => 13,
-        'A' => 14,
-        _ => 0,
+    fn play(&self, row: &str) -> Result<Play, PlayError> {
+        let mut parts = row.split(' ');
+        let cards = parts.next().unwrap_or_default();
+        let found = cards.chars().count();
+        if found != self.hand_size {
+            return Err(PlayError::WrongHandSize {
+                expected: self.hand_size,
+                found,
+            });
+        }
+        let hand = cards
+            .chars()
+            .map(|card| self.rank(card))
+            .collect::<Result<Vec<usize>, PlayError>>()?;
+        let bid = parts
+            .next()
+            .and_then(|bid| bid.parse().ok())
+            .ok_or_else(|| PlayError::InvalidBid(row.to_owned()))?;
+        Ok(Play {
+            bid,
+            category: self.category(cards)?,
+            hand,
+        })
     }
 }
 
-fn card_value_2(value: char) -> usize {
-    match value.into() {
-        '2' => 2,
-        '3' => 3,
-        '4' => 4,
-        '5' => 5,
-        '6' => 6,
-        '7' => 7,
-        '8' => 8,
-        '9' => 9,
-        'T' => 10,
-        'J' => 1,
-        'Q' => 12,
-        'K' => 13,
-        'A' => 14,
-        _ => 0,
-    }
+struct Play {
+    bid: usize,
+    category: usize,
+    hand: Vec<usize>,
 }
 
-fn solution_1(mut plays: Vec<Play>) -> usize {
-    plays.sort_by(|play1, play2| {
-        if play1.hand_value.ne(&play2.hand_value) {
-            return play1.hand_value.cmp(&play2.hand_value);
-        }
-        for (index, card_hand_1) in play1.hand.iter().enumerate() {
-            let card_hand_2 = play2.hand.get(index).unwrap();
-            if card_hand_1.ne(card_hand_2) {
-                return card_hand_1.cmp(card_hand_2);
-            }
-        }
-        std::cmp::Ordering::Equal
-    });
+// Plays rank by category and then card by card
+fn solution(rules: &Rules, rows: &[String]) -> usize {
+    let mut plays: Vec<Play> = rows
+        .iter()
+        .map(|row| rules.play(row).unwrap_or_else(|error| panic!("{}", error)))
+        .collect();
+    plays.sort_by(|play1, play2| (play1.category, &play1.hand).cmp(&(play2.category, &play2.hand)));
+    trace!(
+        Debug,
+        "plays per category: {}",
+        rules
+            .categories
+            .iter()
+            .enumerate()
+            .map(|(index, category)| {
+                let count = plays.iter().filter(|play| play.category == index).count();
+                format!("{} {}", count, category.name)
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    );
     let mut total_winnings = 0;
     for (index, play) in plays.iter().enumerate() {
         total_winnings += play.bid * (index + 1);
@@ -182,131 +186,283 @@ fn solution_1(mut plays: Vec<Play>) -> usize {
     total_winnings
 }
 
-fn solution_2(mut plays: Vec<Play>) -> usize {
-    plays.sort_by(|play1, play2| {
-        if play1.hand_value.ne(&play2.hand_value) {
-            return play1.hand_value.cmp(&play2.hand_value);
-        }
-        for (index, card_hand_1) in play1.hand.iter().enumerate() {
-            let card_hand_2 = play2.hand.get(index).unwrap();
-            if card_hand_1.ne(card_hand_2) {
-                return card_hand_1.cmp(card_hand_2);
-            }
-        }
-        std::cmp::Ordering::Equal
-    });
-    let mut total_winnings = 0;
-    for (index, play) in plays.iter().enumerate() {
-        total_winnings += play.bid * (index + 1);
-    }
-    total_winnings
+pub fn parts() -> Vec<DayPart> {
+    let rows = || get_rows(get_input(file!(), "input1.txt"));
+    vec![
+        DayPart::new(2023, 7, 1, move || {
+            Answer::from(solution(&Rules::camel_cards(), &rows()))
+        })
+        .expecting(253205868),
+        DayPart::new(2023, 7, 2, move || {
+            Answer::from(solution(&Rules::jokers(), &rows()))
+        })
+        .expecting(253907829),
+    ]
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::shared::file_parser::{get_input, get_rows};
+    use crate::shared::{
+        differential::Differential,
+        file_parser::{get_input, get_rows},
+        random::Rng,
+    };
 
     use super::*;
 
+    fn category_name(rules: &Rules, hand: &str) -> &'static str {
+        rules.categories[rules.category(hand).unwrap()].name
+    }
+
     #[test]
     fn test_parser() {
-        let input = "32T3K 765";
-        let play = Play::from(input);
+        let play = Rules::camel_cards().play("32T3K 765").unwrap();
         assert_eq!(765, play.bid);
-        assert_eq!(&3, play.hand.get(0).unwrap());
-        assert_eq!(&2, play.hand.get(1).unwrap());
-        assert_eq!(&10, play.hand.get(2).unwrap());
-        assert_eq!(&3, play.hand.get(3).unwrap());
-        assert_eq!(&13, play.hand.get(4).unwrap());
+        assert_eq!(vec![1, 0, 8, 1, 11], play.hand);
+        assert_eq!(1, play.category);
+        let play = Rules::jokers().play("32J3K 765").unwrap();
+        assert_eq!(vec![2, 1, 0, 2, 11], play.hand);
+        assert_eq!(3, play.category);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let rules = Rules::camel_cards();
+        assert_eq!(
+            Err(PlayError::UnknownCard('X')),
+            rules.play("32X3K 765").map(|p| p.bid)
+        );
+        assert_eq!(
+            Err(PlayError::WrongHandSize {
+                expected: 5,
+                found: 4
+            }),
+            rules.play("32T3 765").map(|p| p.bid)
+        );
+        assert_eq!(
+            Err(PlayError::InvalidBid(String::from("32T3K"))),
+            rules.play("32T3K").map(|p| p.bid)
+        );
     }
 
     #[test]
     fn test_hand_value() {
-        assert_eq!(HandValue::FiveOfAKind, hand_value_1(&vec![3, 3, 3, 3, 3])); // five of a kind
-        assert_eq!(HandValue::FourOfAKind, hand_value_1(&vec![3, 7, 3, 3, 3])); // four of a kind
-        assert_eq!(HandValue::FullHouse, hand_value_1(&vec![3, 12, 3, 12, 3])); // full house
-        assert_eq!(HandValue::ThreeOfAKind, hand_value_1(&vec![2, 3, 3, 3, 9])); // three of a kind
-        assert_eq!(HandValue::TwoPair, hand_value_1(&vec![3, 14, 13, 14, 13])); // two pair
-        assert_eq!(HandValue::Pair, hand_value_1(&vec![2, 10, 3, 10, 13])); // pair
-        assert_eq!(HandValue::HighCard, hand_value_1(&vec![3, 14, 8, 2, 7])); // high ace
-        assert_eq!(HandValue::HighCard, hand_value_1(&vec![13, 3, 7, 4, 2])); // high king
-        assert_eq!(HandValue::HighCard, hand_value_1(&vec![6, 12, 3, 7, 8])); // high queen
-        assert_eq!(HandValue::HighCard, hand_value_1(&vec![6, 3, 11, 8, 4])); // high jack
-        assert_eq!(HandValue::HighCard, hand_value_1(&vec![5, 2, 6, 3, 10])); // high 10
-        assert_eq!(HandValue::HighCard, hand_value_1(&vec![4, 3, 7, 9, 5])); // high 9
+        let rules = Rules::camel_cards();
+        assert_eq!("five of a kind", category_name(&rules, "33333"));
+        assert_eq!("four of a kind", category_name(&rules, "37333"));
+        assert_eq!("full house", category_name(&rules, "3Q3Q3"));
+        assert_eq!("three of a kind", category_name(&rules, "23339"));
+        assert_eq!("two pair", category_name(&rules, "3AKAK"));
+        assert_eq!("one pair", category_name(&rules, "2T3TK"));
+        assert_eq!("high card", category_name(&rules, "3A827")); // high ace
+        assert_eq!("high card", category_name(&rules, "K3742")); // high king
+        assert_eq!("high card", category_name(&rules, "6Q378")); // high queen
+        assert_eq!("high card", category_name(&rules, "63J84")); // high jack
+        assert_eq!("high card", category_name(&rules, "5263T")); // high 10
+        assert_eq!("high card", category_name(&rules, "43795")); // high 9
     }
 
     #[test]
     fn test_hand_value_2() {
-        assert_eq!(HandValue::FiveOfAKind, hand_value_2(&vec![1, 1, 1, 1, 1])); // five of a kind
-        assert_eq!(HandValue::FiveOfAKind, hand_value_2(&vec![3, 1, 3, 3, 3])); // four of a kind
-        assert_eq!(HandValue::FourOfAKind, hand_value_2(&vec![1, 3, 3, 3, 9])); // three of a kind
-        assert_eq!(HandValue::FullHouse, hand_value_2(&vec![1, 14, 13, 14, 13])); // two pair
-        assert_eq!(
-            HandValue::ThreeOfAKind,
-            hand_value_2(&vec![1, 10, 3, 10, 13])
-        ); // pair
-        assert_eq!(HandValue::Pair, hand_value_2(&vec![3, 14, 8, 1, 7])); // high ace
-        assert_eq!(HandValue::Pair, hand_value_2(&vec![13, 1, 7, 4, 2])); // high king
-        assert_eq!(HandValue::Pair, hand_value_2(&vec![6, 1, 3, 7, 8])); // high queen
-        assert_eq!(HandValue::Pair, hand_value_2(&vec![6, 1, 11, 8, 4])); // high jack
-        assert_eq!(HandValue::Pair, hand_value_2(&vec![5, 2, 1, 3, 10])); // high 10
-        assert_eq!(HandValue::Pair, hand_value_2(&vec![4, 3, 1, 9, 5])); // high 9
-
-        assert_eq!(HandValue::Pair, hand_value_2(&vec![3, 2, 10, 3, 13]));
-        assert_eq!(HandValue::TwoPair, hand_value_2(&vec![13, 13, 6, 7, 7]));
-        assert_eq!(HandValue::FourOfAKind, hand_value_2(&vec![10, 5, 5, 1, 5]));
-        assert_eq!(
-            HandValue::FourOfAKind,
-            hand_value_2(&vec![13, 10, 1, 1, 10])
-        );
+        let rules = Rules::jokers();
+        assert_eq!("five of a kind", category_name(&rules, "JJJJJ"));
+        assert_eq!("five of a kind", category_name(&rules, "3J333")); // four of a kind
+        assert_eq!("four of a kind", category_name(&rules, "J3339")); // three of a kind
+        assert_eq!("full house", category_name(&rules, "JAKAK")); // two pair
+        assert_eq!("three of a kind", category_name(&rules, "JT3TK")); // pair
+        assert_eq!("one pair", category_name(&rules, "3A8J7")); // high ace
+        assert_eq!("one pair", category_name(&rules, "KJ742")); // high king
+        assert_eq!("one pair", category_name(&rules, "6J378")); // high queen
+        assert_eq!("one pair", category_name(&rules, "6JQ84")); // high jack
+        assert_eq!("one pair", category_name(&rules, "52J3T")); // high 10
+        assert_eq!("one pair", category_name(&rules, "43J95")); // high 9
+
+        assert_eq!("one pair", category_name(&rules, "32T3K"));
+        assert_eq!("two pair", category_name(&rules, "KK677"));
+        assert_eq!("four of a kind", category_name(&rules, "T55J5"));
+        assert_eq!("four of a kind", category_name(&rules, "KTJJT"));
+        assert_eq!("four of a kind", category_name(&rules, "QQQJA"));
+    }
+
+    #[test]
+    fn other_rule_sets() {
+        // two kinds of wildcards, both below every other card
+        let rules = Rules::camel_cards().with_wildcards("J2");
+        assert_eq!(vec!['J', '2', '3'], rules.card_order[..3]);
+        assert_eq!("five of a kind", category_name(&rules, "J2J2A"));
+        assert_eq!("full house", category_name(&rules, "KK2AA"));
+        assert_eq!("four of a kind", category_name(&rules, "KK2JA"));
+
+        // six cards with a category only they can make
+        let mut categories = poker_categories();
+        categories.insert(3, Category::new("three pair", &[2, 2, 2]));
+        categories.push(Category::new("six of a kind", &[6]));
+        let rules = Rules {
+            hand_size: 6,
+            categories,
+            ..Rules::jokers()
+        };
+        assert_eq!("three pair", category_name(&rules, "KK3377"));
+        // the joker could finish the third pair, a full house is worth more
+        assert_eq!("full house", category_name(&rules, "KK337J"));
+        assert_eq!("six of a kind", category_name(&rules, "AJAJJA"));
+        assert_eq!("full house", category_name(&rules, "KKK33A"));
+        let rows = vec![
+            String::from("KK337J 10"),
+            String::from("AJAJJA 1"),
+            String::from("23456T 100"),
+        ];
+        assert_eq!(100 + 2 * 10 + 3, solution(&rules, &rows));
         assert_eq!(
-            HandValue::FourOfAKind,
-            hand_value_2(&vec![12, 12, 12, 1, 14])
+            Err(PlayError::WrongHandSize {
+                expected: 6,
+                found: 5
+            }),
+            rules.play("32T3K 765").map(|p| p.bid)
         );
     }
 
     #[test]
     fn example_1_test() {
-        let input = get_input(file!(), "example1.txt");
-        let rows = get_rows(input);
-        let plays = rows
-            .into_iter()
-            .map(|row| Play::from(row.as_str()))
-            .collect();
-        assert_eq!(6440, solution_1(plays));
+        let rows = get_rows(get_input(file!(), "example1.txt"));
+        assert_eq!(6440, solution(&Rules::camel_cards(), &rows));
     }
 
     #[test]
     fn solution_1_test() {
-        let input = get_input(file!(), "input1.txt");
-        let rows = get_rows(input);
-        let plays = rows
-            .into_iter()
-            .map(|row| Play::from(row.as_str()))
-            .collect();
-        assert_eq!(253205868, solution_1(plays));
+        let rows = get_rows(get_input(file!(), "input1.txt"));
+        assert_eq!(253205868, solution(&Rules::camel_cards(), &rows));
     }
 
     #[test]
     fn example_2_test() {
-        let input = get_input(file!(), "example1.txt");
-        let rows = get_rows(input);
-        let plays = rows
-            .into_iter()
-            .map(|row| Play::from_2(row.as_str()))
-            .collect();
-        assert_eq!(5905, solution_2(plays));
+        let rows = get_rows(get_input(file!(), "example1.txt"));
+        assert_eq!(5905, solution(&Rules::jokers(), &rows));
     }
 
     #[test]
     fn solution_2_test() {
-        let input = get_input(file!(), "input1.txt");
-        let rows = get_rows(input);
-        let plays = rows
+        let rows = get_rows(get_input(file!(), "input1.txt"));
+        assert_eq!(253907829, solution(&Rules::jokers(), &rows));
+    }
+
+    const CARDS: [char; 13] = [
+        '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
+    ];
+
+    // Reference for wildcards: the best category without wildcards over every
+    // card the first wildcard can stand in for. Cards that are not in the hand
+    // all give the same category, so only one of them has to be tried.
+    fn best_replacement(wildcards: &str, hand: &str) -> usize {
+        let first_wildcard = match hand.find(|c| wildcards.contains(c)) {
+            Some(index) => index,
+            None => return Rules::camel_cards().category(hand).unwrap(),
+        };
+        let absent = CARDS
+            .iter()
+            .find(|c| !wildcards.contains(**c) && !hand.contains(**c));
+        CARDS
+            .iter()
+            .filter(|c| !wildcards.contains(**c) && (hand.contains(**c) || Some(*c) == absent))
+            .map(|c| {
+                let mut replaced = hand.to_owned();
+                replaced.replace_range(first_wildcard..first_wildcard + 1, &c.to_string());
+                best_replacement(wildcards, &replaced)
+            })
+            .max()
+            .unwrap()
+    }
+
+    // Hands shrink by swapping a card for the lowest card that is not used yet,
+    // wildcards are left alone as they are what is being checked
+    fn shrink_hand(hand: &str, wildcards: &str) -> Vec<String> {
+        let rank = |card: char| CARDS.iter().position(|c| *c == card);
+        let mut candidates = Vec::new();
+        if let Some(unused) = CARDS
+            .iter()
+            .find(|c| !wildcards.contains(**c) && !hand.contains(**c))
+        {
+            for (index, card) in hand.char_indices() {
+                if !wildcards.contains(card) && rank(card) > rank(*unused) {
+                    let mut candidate = hand.to_owned();
+                    candidate.replace_range(index..index + 1, &unused.to_string());
+                    candidates.push(candidate);
+                }
+            }
+        }
+        candidates
+    }
+
+    fn random_hand(rng: &mut Rng) -> String {
+        // only a few different cards so pairs and jokers show up often
+        let cards: Vec<char> = (0..rng.range(1..6)).map(|_| *rng.pick(&CARDS)).collect();
+        let cards = [cards, vec!['J']].concat();
+        (0..5).map(|_| *rng.pick(&cards)).collect()
+    }
+
+    fn hands(file: &str) -> Vec<String> {
+        get_rows(get_input(file!(), file))
             .into_iter()
-            .map(|row| Play::from_2(row.as_str()))
-            .collect();
-        assert_eq!(253907829, solution_2(plays));
+            .map(|row| row.split(' ').next().unwrap().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn differential_hand_values_without_jokers() {
+        let differential = Differential::new(
+            "camel_cards",
+            |hand: &String| Rules::camel_cards().category(hand).unwrap(),
+            "jokers",
+            |hand: &String| Rules::jokers().category(hand).unwrap(),
+        )
+        .with_shrinker(|hand| shrink_hand(hand, "J"));
+        let without_jokers = |hands: Vec<String>| {
+            hands
+                .into_iter()
+                .filter(|hand| !hand.contains('J'))
+                .collect::<Vec<String>>()
+        };
+        let result = differential
+            .check(without_jokers(hands("input1.txt")))
+            .and_then(|_| {
+                differential.check_random(7, 2000, |rng| random_hand(rng).replace('J', "Q"))
+            });
+        if let Err(disagreement) = result {
+            panic!("{}", disagreement);
+        }
+    }
+
+    #[test]
+    fn differential_hand_value_2_against_joker_replacement() {
+        let differential = Differential::new(
+            "jokers",
+            |hand: &String| Rules::jokers().category(hand).unwrap(),
+            "best_replacement",
+            |hand| best_replacement("J", hand),
+        )
+        .with_shrinker(|hand| shrink_hand(hand, "J"));
+        let result = differential
+            .check(hands("input1.txt"))
+            .and_then(|_| differential.check_random(7, 2000, random_hand));
+        if let Err(disagreement) = result {
+            panic!("{}", disagreement);
+        }
+    }
+
+    #[test]
+    fn differential_two_wildcards_against_replacement() {
+        let rules = Rules::camel_cards().with_wildcards("J9");
+        let differential = Differential::new(
+            "two wildcards",
+            |hand: &String| rules.category(hand).unwrap(),
+            "best_replacement",
+            |hand| best_replacement("J9", hand),
+        )
+        .with_shrinker(|hand| shrink_hand(hand, "J9"));
+        let result =
+            differential.check_random(11, 2000, |rng| random_hand(rng).replacen('J', "9", 1));
+        if let Err(disagreement) = result {
+            panic!("{}", disagreement);
+        }
     }
 }
@@ -1,5 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
+use crate::shared::{answer::Answer, file_parser::get_trimmed_input, registry::DayPart};
+
 #[derive(Debug, PartialEq, Eq)]
 enum Direction {
     Left,
@@ -135,83 +137,154 @@ fn solution_1(game: Game, start: LookupString, end: LookupString) -> usize {
     count
 }
 
-fn solution_2(game: Game, start: char, end: char) -> usize {
-    let mut current_keys = game.get_starting_points(start);
-    let mut instruction_index = 0;
-    let last_instruction_index = game.instructions.value.len() - 1;
-    let mut test: HashMap<LookupString, usize> = HashMap::new();
-    for key in current_keys {
-        test.insert(key, 0);
-
-        let mut current_val = key;
-        let mut instruction_index = 0;
-        while current_val.last().unwrap().ne(&end) {
-            if instruction_index > last_instruction_index {
-                instruction_index = 0;
-            }
-            test.entry(key).and_modify(|v| *v += 1);
-            current_val = game.get_next(
-                current_val,
-                game.instructions.value.get(instruction_index).unwrap(),
-            );
-            instruction_index += 1;
+// Where a ghost is after every step: a tail walked once, then a loop repeated
+// forever. A state is a node together with the position in the instructions,
+// as the same node goes on differently at another position.
+#[derive(Debug, PartialEq)]
+struct GhostCycle {
+    prefix: usize,
+    length: usize,
+    // steps in the tail that end on an end node
+    prefix_hits: Vec<usize>,
+    // steps into the loop that end on an end node
+    cycle_hits: Vec<usize>,
+}
+
+impl GhostCycle {
+    fn is_hit(&self, step: u128) -> bool {
+        if step < self.prefix as u128 {
+            return self.prefix_hits.contains(&(step as usize));
         }
+        let into_cycle = (step - self.prefix as u128) % self.length as u128;
+        self.cycle_hits.contains(&(into_cycle as usize))
     }
-
-    least_common_multiple(
-        test.values()
-            .into_iter()
-            .map(|v| *v)
-            .collect::<Vec<usize>>(),
-    )
 }
 
-fn prime_factorization(mut num: usize) -> HashMap<usize, usize> {
-    let mut factors = HashMap::new();
-    let mut divisor = 2;
-
-    while num > 1 {
-        while num % divisor == 0 {
-            *factors.entry(divisor).or_insert(0) += 1;
-            num /= divisor;
+impl Game {
+    fn get_cycle(&self, start: LookupString, end: char) -> GhostCycle {
+        let instructions = &self.instructions.value;
+        let mut first_seen: HashMap<(LookupString, usize), usize> = HashMap::new();
+        let mut hits = Vec::new();
+        let mut current_val = start;
+        let mut step = 0;
+        loop {
+            let state = (current_val, step % instructions.len());
+            if let Some(&prefix) = first_seen.get(&state) {
+                let (prefix_hits, cycle_hits): (Vec<usize>, Vec<usize>) =
+                    hits.into_iter().partition(|hit| *hit < prefix);
+                return GhostCycle {
+                    prefix,
+                    length: step - prefix,
+                    prefix_hits,
+                    cycle_hits: cycle_hits.into_iter().map(|hit| hit - prefix).collect(),
+                };
+            }
+            first_seen.insert(state, step);
+            if current_val.last().unwrap().eq(&end) {
+                hits.push(step);
+            }
+            current_val = self.get_next(current_val, &instructions[state.1]);
+            step += 1;
         }
-
-        divisor += 1;
     }
+}
 
-    factors
+// First step at which every ghost is on an end node, None when that never
+// happens
+fn solution_2(game: Game, start: char, end: char) -> Option<u128> {
+    let cycles: Vec<GhostCycle> = game
+        .get_starting_points(start)
+        .into_iter()
+        .map(|key| game.get_cycle(key, end))
+        .collect();
+    // the tails are no longer than the states that were walked, so every step
+    // before all ghosts are in their loop can simply be tried
+    let tail = cycles.iter().map(|cycle| cycle.prefix).max().unwrap_or(0) as u128;
+    if let Some(step) = (0..tail).find(|step| cycles.iter().all(|cycle| cycle.is_hit(*step))) {
+        return Some(step);
+    }
+    // from then on a ghost is on an end node at the steps congruent to one of
+    // its hits modulo its loop length, all of them at once at the steps that
+    // satisfy one such congruence per ghost
+    let mut residues = vec![0];
+    let mut modulus = 1;
+    for cycle in &cycles {
+        let length = cycle.length as u128;
+        let mut merged: Vec<u128> = residues
+            .iter()
+            .flat_map(|residue| {
+                cycle.cycle_hits.iter().filter_map(move |hit| {
+                    let hit = (cycle.prefix + hit) as u128 % length;
+                    combine_congruences((*residue, modulus), (hit, length))
+                })
+            })
+            .collect();
+        merged.sort();
+        merged.dedup();
+        residues = merged;
+        modulus = modulus / greatest_common_divisor(modulus, length) * length;
+    }
+    residues
+        .into_iter()
+        .map(|residue| match residue >= tail {
+            true => residue,
+            false => residue + (tail - residue).div_ceil(modulus) * modulus,
+        })
+        .min()
 }
 
-// Function to find the least common multiple
-fn least_common_multiple(numbers: Vec<usize>) -> usize {
-    // Collect prime factorizations of all numbers
-    let mut all_factors = Vec::new();
-    for &num in &numbers {
-        all_factors.push(prime_factorization(num));
+fn greatest_common_divisor(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
     }
+    a
+}
 
-    // Compute common prime factors
-    let mut common_factors = HashMap::new();
-    for factors in all_factors.iter() {
-        for (&factor, &count) in factors.iter() {
-            *common_factors.entry(factor).or_insert(usize::MIN) = common_factors
-                .get(&factor)
-                .map_or(count, |&max_count| max_count.max(count));
-        }
+// Inverse of value modulo modulus by the extended euclidean algorithm, the two
+// have to be coprime
+fn modular_inverse(value: u128, modulus: u128) -> u128 {
+    let (mut old_remainder, mut remainder) = (value as i128, modulus as i128);
+    let (mut old_coefficient, mut coefficient) = (1i128, 0i128);
+    while remainder != 0 {
+        let quotient = old_remainder / remainder;
+        (old_remainder, remainder) = (remainder, old_remainder - quotient * remainder);
+        (old_coefficient, coefficient) = (coefficient, old_coefficient - quotient * coefficient);
     }
+    old_coefficient.rem_euclid(modulus as i128) as u128
+}
 
-    // Multiply common prime factors to find the LCM
-    let mut lcm = 1;
-    for (&factor, &count) in common_factors.iter() {
-        lcm *= factor.pow(count as u32);
+// Chinese remainder theorem for moduli that need not be coprime: the numbers
+// that are a1 modulo m1 and a2 modulo m2 are one residue modulo lcm(m1, m2),
+// or there are none when a1 and a2 disagree on the common divisor
+fn combine_congruences((a1, m1): (u128, u128), (a2, m2): (u128, u128)) -> Option<u128> {
+    let divisor = greatest_common_divisor(m1, m2);
+    if a1.abs_diff(a2) % divisor != 0 {
+        return None;
     }
+    let m2_reduced = m2 / divisor;
+    // a1 + m1 * k = a2 (mod m2) solved for k
+    let difference = (a2 + m2 - a1 % m2) % m2 / divisor;
+    let k = difference * modular_inverse(m1 / divisor % m2_reduced, m2_reduced) % m2_reduced;
+    Some((a1 + m1 * k) % (m1 * m2_reduced))
+}
 
-    lcm
+pub fn parts() -> Vec<DayPart> {
+    let game = || Game::from(get_trimmed_input(file!(), "input1.txt"));
+    vec![
+        DayPart::new(2023, 8, 1, move || {
+            Answer::from(solution_1(game(), ['A', 'A', 'A'], ['Z', 'Z', 'Z']))
+        })
+        .expecting(19667),
+        DayPart::new(2023, 8, 2, move || {
+            Answer::from(solution_2(game(), 'A', 'Z').unwrap())
+        })
+        .expecting(19185263738117u64),
+    ]
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::shared::file_parser::get_input;
+    use crate::shared::{differential::Differential, file_parser::get_trimmed_input, random::Rng};
 
     use super::*;
 
@@ -233,7 +306,7 @@ mod tests {
 
     #[test]
     fn example_1_test() {
-        let input = get_input(file!(), "example1.txt");
+        let input = get_trimmed_input(file!(), "example1.txt");
 
         let game = Game::from(input);
         assert_eq!(6, solution_1(game, ['A', 'A', 'A'], ['Z', 'Z', 'Z']));
@@ -241,7 +314,7 @@ mod tests {
 
     #[test]
     fn solution_1_test() {
-        let input = get_input(file!(), "input1.txt");
+        let input = get_trimmed_input(file!(), "input1.txt");
 
         let game = Game::from(input);
         assert_eq!(19667, solution_1(game, ['A', 'A', 'A'], ['Z', 'Z', 'Z']));
@@ -249,15 +322,135 @@ mod tests {
 
     #[test]
     fn example_2_test() {
-        let input = get_input(file!(), "example2.txt");
+        let input = get_trimmed_input(file!(), "example2.txt");
         let game = Game::from(input);
-        assert_eq!(6, solution_2(game, 'A', 'Z'));
+        assert_eq!(Some(6), solution_2(game, 'A', 'Z'));
     }
 
     #[test]
     fn solution_2_test() {
-        let input = get_input(file!(), "input1.txt");
+        let input = get_trimmed_input(file!(), "input1.txt");
         let game = Game::from(input);
-        assert_eq!(19185263738117, solution_2(game, 'A', 'Z'));
+        assert_eq!(Some(19185263738117), solution_2(game, 'A', 'Z'));
+    }
+
+    // Moves all ghosts at once until they are all on an end node
+    fn walk_ghosts(game: &Game, start: char, end: char, limit: usize) -> Option<u128> {
+        let instructions = &game.instructions.value;
+        let mut ghosts: Vec<LookupString> = game.get_starting_points(start).into_iter().collect();
+        for step in 0..limit {
+            if ghosts.iter().all(|ghost| ghost.last().unwrap().eq(&end)) {
+                return Some(step as u128);
+            }
+            let direction = &instructions[step % instructions.len()];
+            ghosts = ghosts
+                .into_iter()
+                .map(|ghost| game.get_next(ghost, direction))
+                .collect();
+        }
+        None
+    }
+
+    // The first ghost reaches an end node after 2 steps and then every 2, the
+    // second after 1 and then every 3, the third twice per loop of 4
+    const OFFSET_GHOSTS: &str = "L\n\n\
+        11A = (11B, XXX)\n\
+        11B = (11Z, XXX)\n\
+        11Z = (11C, XXX)\n\
+        11C = (11Z, XXX)\n\
+        22A = (22Z, XXX)\n\
+        22Z = (22B, XXX)\n\
+        22B = (22C, XXX)\n\
+        22C = (22Z, XXX)\n\
+        33A = (33Z, XXX)\n\
+        33Z = (3BZ, XXX)\n\
+        3BZ = (33C, XXX)\n\
+        33C = (33A, XXX)\n\
+        XXX = (XXX, XXX)";
+
+    #[test]
+    fn ghost_cycles() {
+        let game = Game::from(String::from(OFFSET_GHOSTS));
+        assert_eq!(
+            GhostCycle {
+                prefix: 2,
+                length: 2,
+                prefix_hits: vec![],
+                cycle_hits: vec![0],
+            },
+            game.get_cycle(['1', '1', 'A'], 'Z')
+        );
+        assert_eq!(
+            GhostCycle {
+                prefix: 0,
+                length: 4,
+                prefix_hits: vec![],
+                cycle_hits: vec![1, 2],
+            },
+            game.get_cycle(['3', '3', 'A'], 'Z')
+        );
+    }
+
+    #[test]
+    fn offset_cycles_with_several_hits() {
+        let game = Game::from(String::from(OFFSET_GHOSTS));
+        assert_eq!(Some(10), walk_ghosts(&game, 'A', 'Z', 100));
+        assert_eq!(Some(10), solution_2(game, 'A', 'Z'));
+    }
+
+    #[test]
+    fn ghosts_that_never_meet() {
+        let input = "L\n\n\
+            11A = (11Z, XXX)\n\
+            11Z = (11A, XXX)\n\
+            22A = (22B, XXX)\n\
+            22B = (22Z, XXX)\n\
+            22Z = (22B, XXX)\n\
+            XXX = (XXX, XXX)";
+        assert_eq!(None, solution_2(Game::from(String::from(input)), 'A', 'Z'));
+    }
+
+    #[test]
+    fn congruences() {
+        assert_eq!(Some(10), combine_congruences((4, 6), (2, 4)));
+        assert_eq!(None, combine_congruences((1, 6), (2, 4)));
+        assert_eq!(Some(8), combine_congruences((2, 3), (3, 5)));
+        assert_eq!(Some(23), combine_congruences((8, 15), (2, 7)));
+    }
+
+    fn random_ghosts(rng: &mut Rng) -> String {
+        let nodes = rng.range(2..9);
+        let name = |index: usize, rng: &mut Rng| {
+            let last = match index {
+                0 | 1 => 'A',
+                _ if rng.chance(1, 3) => 'Z',
+                _ => 'X',
+            };
+            format!("{:02}{}", index, last)
+        };
+        let names: Vec<String> = (0..nodes).map(|index| name(index, rng)).collect();
+        let instructions: String = (0..rng.range(1..4))
+            .map(|_| *rng.pick(&['L', 'R']))
+            .collect();
+        let rows: Vec<String> = names
+            .iter()
+            .map(|node| format!("{} = ({}, {})", node, rng.pick(&names), rng.pick(&names)))
+            .collect();
+        format!("{}\n\n{}", instructions, rows.join("\n"))
+    }
+
+    #[test]
+    fn differential_random_ghosts() {
+        let differential = Differential::new(
+            "solution_2",
+            |input: &String| solution_2(Game::from(input.clone()), 'A', 'Z'),
+            "walk_ghosts",
+            // with at most 24 states per ghost they meet within 24 + 24 * 24 steps
+            |input: &String| walk_ghosts(&Game::from(input.clone()), 'A', 'Z', 1_000),
+        );
+        let result = differential.check_random(8, 2000, random_ghosts);
+        if let Err(disagreement) = result {
+            panic!("{}", disagreement);
+        }
     }
 }
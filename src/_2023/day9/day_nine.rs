@@ -1,54 +1,143 @@
-fn solution_1(input: Vec<Vec<isize>>) -> isize {
-    let mut count = 0;
-    for row in input {
-        let mut last_value_from_each = vec![*row.last().unwrap()];
-        let mut current_row = row;
-        while current_row.first().unwrap() != &0isize || current_row.last().unwrap() != &0isize {
-            let mut next_row = Vec::new();
-            for (index, value) in current_row.iter().enumerate() {
-                match current_row.get(index + 1) {
-                    Some(next_value) => next_row.push(next_value - value),
-                    None => last_value_from_each.push(*next_row.last().unwrap()),
-                }
-            }
-            current_row = next_row;
+use std::fmt;
+
+use crate::{
+    shared::{
+        answer::Answer,
+        file_parser::{get_input, get_rows},
+        registry::DayPart,
+    },
+    trace,
+};
+
+#[derive(Debug, PartialEq)]
+enum ExtrapolationError {
+    Empty,
+    Overflow,
+}
+
+impl fmt::Display for ExtrapolationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtrapolationError::Empty => write!(f, "an empty history predicts nothing"),
+            ExtrapolationError::Overflow => write!(f, "the prediction does not fit an isize"),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Forward,
+    Backward,
+}
+
+// A history as the polynomial through its values, kept as the first value of
+// every row of differences (Newton's forward differences)
+#[derive(Debug, PartialEq)]
+struct Sequence {
+    length: usize,
+    leading_differences: Vec<isize>,
+}
+
+impl TryFrom<&[isize]> for Sequence {
+    type Error = ExtrapolationError;
+
+    fn try_from(values: &[isize]) -> Result<Self, Self::Error> {
+        if values.is_empty() {
+            return Err(ExtrapolationError::Empty);
         }
-        // removing zero
-        last_value_from_each.remove(last_value_from_each.len() - 1);
-        last_value_from_each.reverse();
-        let mut value_to_add = 0;
-        for v in last_value_from_each {
-            value_to_add += v;
+        let mut leading_differences = Vec::new();
+        let mut current_row = values.to_vec();
+        // only a row that is zero everywhere ends the differencing
+        while current_row.iter().any(|value| *value != 0) {
+            leading_differences.push(current_row[0]);
+            current_row = current_row
+                .windows(2)
+                .map(|pair| pair[1].checked_sub(pair[0]))
+                .collect::<Option<Vec<isize>>>()
+                .ok_or(ExtrapolationError::Overflow)?;
         }
-        count += value_to_add;
+        Ok(Self {
+            length: values.len(),
+            leading_differences,
+        })
     }
-    count
 }
 
-fn solution_2(input: Vec<Vec<isize>>) -> isize {
-    let mut count = 0;
-    for row in input {
-        let mut first_value_from_each = Vec::new();
-        let mut current_row = row;
-        while current_row.first().unwrap() != &0isize || current_row.last().unwrap() != &0isize {
-            let mut next_row = Vec::new();
-            first_value_from_each.push(*current_row.first().unwrap());
-            for (index, value) in current_row.iter().enumerate() {
-                match current_row.get(index + 1) {
-                    Some(next_value) => next_row.push(next_value - value),
-                    None => (),
-                }
+impl Sequence {
+    // None for a history that is zero everywhere
+    fn degree(&self) -> Option<usize> {
+        self.leading_differences.len().checked_sub(1)
+    }
+
+    // f(position) = sum over j of binomial(position, j) * leading_differences[j],
+    // which also holds for positions before the first value. The terms can go
+    // past isize and cancel out again, only the final value has to fit.
+    fn value_at(&self, position: isize) -> Result<isize, ExtrapolationError> {
+        let position = position as i128;
+        let mut value: i128 = 0;
+        let mut binomial: i128 = 1;
+        for (j, difference) in self.leading_differences.iter().enumerate() {
+            if j > 0 {
+                // binomial(position, j) = binomial(position, j - 1) * (position - j + 1) / j
+                binomial = binomial
+                    .checked_mul(position - j as i128 + 1)
+                    .ok_or(ExtrapolationError::Overflow)?
+                    / j as i128;
             }
-            current_row = next_row;
-        }
-        first_value_from_each.reverse();
-        let mut value_to_add = 0;
-        for v in first_value_from_each {
-            value_to_add = v - value_to_add;
+            value = binomial
+                .checked_mul(*difference as i128)
+                .and_then(|term| value.checked_add(term))
+                .ok_or(ExtrapolationError::Overflow)?;
         }
-        count += value_to_add;
+        isize::try_from(value).map_err(|_| ExtrapolationError::Overflow)
     }
-    count
+
+    // The next `count` values after the last one, or before the first one
+    // going back
+    fn extrapolate(
+        &self,
+        direction: Direction,
+        count: usize,
+    ) -> Result<Vec<isize>, ExtrapolationError> {
+        let length = isize::try_from(self.length).map_err(|_| ExtrapolationError::Overflow)?;
+        (1..=count as isize)
+            .map(|step| match direction {
+                Direction::Forward => self.value_at(length - 1 + step),
+                Direction::Backward => self.value_at(-step),
+            })
+            .collect()
+    }
+}
+
+fn solution(histories: &[Vec<isize>], direction: Direction) -> Result<isize, ExtrapolationError> {
+    histories.iter().try_fold(0isize, |total, history| {
+        let sequence = Sequence::try_from(history.as_slice())?;
+        trace!(Trace, "{:?} has degree {:?}", history, sequence.degree());
+        let prediction = sequence.extrapolate(direction, 1)?[0];
+        total
+            .checked_add(prediction)
+            .ok_or(ExtrapolationError::Overflow)
+    })
+}
+
+fn parse_histories(rows: Vec<String>) -> Vec<Vec<isize>> {
+    rows.iter()
+        .map(|row| row.split(' ').map(|n| n.parse().unwrap()).collect())
+        .collect()
+}
+
+pub fn parts() -> Vec<DayPart> {
+    let histories = || parse_histories(get_rows(get_input(file!(), "input1.txt")));
+    vec![
+        DayPart::new(2023, 9, 1, move || {
+            Answer::from(solution(&histories(), Direction::Forward).unwrap())
+        })
+        .expecting(2098530125),
+        DayPart::new(2023, 9, 2, move || {
+            Answer::from(solution(&histories(), Direction::Backward).unwrap())
+        })
+        .expecting(1016),
+    ]
 }
 
 #[cfg(test)]
@@ -57,63 +146,102 @@ mod tests {
 
     use super::*;
 
+    fn sequence(values: &[isize]) -> Sequence {
+        Sequence::try_from(values).unwrap()
+    }
+
     #[test]
     fn example_1_test() {
-        let input = get_input(file!(), "example1.txt");
-        let rows = get_rows(input);
-        let test = rows
-            .into_iter()
-            .map(|row| {
-                row.split(" ")
-                    .map(|str| str.parse::<isize>().unwrap())
-                    .collect::<Vec<isize>>()
-            })
-            .collect::<Vec<Vec<isize>>>();
-        assert_eq!(114, solution_1(test));
+        let histories = parse_histories(get_rows(get_input(file!(), "example1.txt")));
+        assert_eq!(Ok(114), solution(&histories, Direction::Forward));
     }
 
     #[test]
     fn solution_1_test() {
-        let input = get_input(file!(), "input1.txt");
-        let rows = get_rows(input);
-        let test = rows
-            .into_iter()
-            .map(|row| {
-                row.split(" ")
-                    .map(|str| str.parse::<isize>().unwrap())
-                    .collect::<Vec<isize>>()
-            })
-            .collect::<Vec<Vec<isize>>>();
-        assert_eq!(2098530125, solution_1(test));
+        let histories = parse_histories(get_rows(get_input(file!(), "input1.txt")));
+        assert_eq!(Ok(2098530125), solution(&histories, Direction::Forward));
     }
 
     #[test]
     fn example_2_test() {
-        let input = get_input(file!(), "example1.txt");
-        let rows = get_rows(input);
-        let test = rows
-            .into_iter()
-            .map(|row| {
-                row.split(" ")
-                    .map(|str| str.parse::<isize>().unwrap())
-                    .collect::<Vec<isize>>()
-            })
-            .collect::<Vec<Vec<isize>>>();
-        assert_eq!(2, solution_2(test));
+        let histories = parse_histories(get_rows(get_input(file!(), "example1.txt")));
+        assert_eq!(Ok(2), solution(&histories, Direction::Backward));
     }
 
     #[test]
     fn solution_2_test() {
-        let input = get_input(file!(), "input1.txt");
-        let rows = get_rows(input);
-        let test = rows
-            .into_iter()
-            .map(|row| {
-                row.split(" ")
-                    .map(|str| str.parse::<isize>().unwrap())
-                    .collect::<Vec<isize>>()
-            })
-            .collect::<Vec<Vec<isize>>>();
-        assert_eq!(1016, solution_2(test));
+        let histories = parse_histories(get_rows(get_input(file!(), "input1.txt")));
+        assert_eq!(Ok(1016), solution(&histories, Direction::Backward));
+    }
+
+    #[test]
+    fn rows_only_zero_at_the_ends() {
+        let row = sequence(&[0, 3, 0]);
+        assert_eq!(Some(2), row.degree());
+        assert_eq!(Ok(vec![-9, -24]), row.extrapolate(Direction::Forward, 2));
+        assert_eq!(Ok(vec![-9, -24]), row.extrapolate(Direction::Backward, 2));
+    }
+
+    #[test]
+    fn degrees() {
+        assert_eq!(Some(1), sequence(&[0, 3, 6, 9, 12, 15]).degree());
+        assert_eq!(Some(2), sequence(&[1, 3, 6, 10, 15, 21]).degree());
+        assert_eq!(Some(3), sequence(&[10, 13, 16, 21, 30, 45]).degree());
+        assert_eq!(Some(0), sequence(&[7, 7, 7]).degree());
+        assert_eq!(Some(0), sequence(&[4]).degree());
+        assert_eq!(None, sequence(&[0, 0]).degree());
+        assert_eq!(Err(ExtrapolationError::Empty), Sequence::try_from(&[][..]));
+    }
+
+    #[test]
+    fn several_values_both_ways() {
+        let triangular = sequence(&[1, 3, 6, 10, 15, 21]);
+        assert_eq!(
+            Ok(vec![28, 36, 45]),
+            triangular.extrapolate(Direction::Forward, 3)
+        );
+        assert_eq!(
+            Ok(vec![0, 0, 1, 3]),
+            triangular.extrapolate(Direction::Backward, 4)
+        );
+        let cubic = sequence(&[1, 8, 27, 64]);
+        assert_eq!(Ok(vec![125, 216]), cubic.extrapolate(Direction::Forward, 2));
+        assert_eq!(
+            Ok(vec![0, -1, -8]),
+            cubic.extrapolate(Direction::Backward, 3)
+        );
+        assert_eq!(Ok(vec![]), cubic.extrapolate(Direction::Forward, 0));
+    }
+
+    #[test]
+    fn overflow_is_reported() {
+        let climbing = sequence(&[isize::MAX - 2, isize::MAX - 1]);
+        assert_eq!(
+            Ok(vec![isize::MAX]),
+            climbing.extrapolate(Direction::Forward, 1)
+        );
+        assert_eq!(
+            Err(ExtrapolationError::Overflow),
+            climbing.extrapolate(Direction::Forward, 2)
+        );
+        assert_eq!(
+            Err(ExtrapolationError::Overflow),
+            Sequence::try_from(&[isize::MIN, isize::MAX][..])
+        );
+        // MAX - 1 + 3 * 1 - 3 * 1 goes past isize on the way
+        let levelling = sequence(&[isize::MAX - 1, isize::MAX, isize::MAX]);
+        assert_eq!(
+            Ok(vec![isize::MAX - 1]),
+            levelling.extrapolate(Direction::Forward, 1)
+        );
+        assert_eq!(
+            Ok(vec![isize::MAX - 3]),
+            levelling.extrapolate(Direction::Backward, 1)
+        );
+        let histories = vec![vec![isize::MAX], vec![1]];
+        assert_eq!(
+            Err(ExtrapolationError::Overflow),
+            solution(&histories, Direction::Forward)
+        );
     }
 }
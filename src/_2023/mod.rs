@@ -1,3 +1,5 @@
+use crate::shared::{animation::DayAnimation, image_export::DayImage, registry::DayPart};
+
 mod day1;
 mod day10;
 mod day11;
@@ -15,3 +17,46 @@ mod day6;
 mod day7;
 mod day8;
 mod day9;
+
+// Every part this year's solutions can run on the real input
+pub fn parts() -> Vec<DayPart> {
+    [
+        day1::day_one::parts(),
+        day2::day_two::parts(),
+        day3::day_three::parts(),
+        day4::day_four::parts(),
+        day5::day_five::parts(),
+        day6::day_six::parts(),
+        day7::day_seven::parts(),
+        day8::day_eight::parts(),
+        day9::day_nine::parts(),
+        day10::day_ten::parts(),
+        day11::day_eleven::parts(),
+        day12::day_twelve::parts(),
+        day13::day_thirteen::parts(),
+        day14::day_fourteen::parts(),
+        day15::day_fifteen::parts(),
+        day16::day_sixteen::parts(),
+        day17::day_seventeen::parts(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+// Every day that can be watched with the animate command
+pub fn animations() -> Vec<DayAnimation> {
+    vec![
+        day14::day_fourteen::animation(),
+        day16::day_sixteen::animation(),
+    ]
+}
+
+// Every day that can be drawn with the export command
+pub fn images() -> Vec<DayImage> {
+    vec![
+        day10::day_ten::image(),
+        day16::day_sixteen::image(),
+        day17::day_seventeen::image(),
+    ]
+}
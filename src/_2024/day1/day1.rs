@@ -2,6 +2,8 @@ use std::{collections::HashMap, ops::AddAssign};
 
 use regex::Regex;
 
+use crate::shared::{answer::Answer, file_parser::get_input, registry::DayPart};
+
 fn parse_input(input: String) -> (Vec<u32>, Vec<u32>) {
     Regex::new(r"(\d+)   (\d+)")
         .unwrap()
@@ -53,6 +55,20 @@ fn calculate_similarity(input: (Vec<u32>, Vec<u32>)) -> u32 {
     result
 }
 
+pub fn parts() -> Vec<DayPart> {
+    let lists = || parse_input(get_input(file!(), "input.txt"));
+    vec![
+        DayPart::new(2024, 1, 1, move || {
+            Answer::from(calculate_avg_distance(lists()))
+        })
+        .expecting(1603498),
+        DayPart::new(2024, 1, 2, move || {
+            Answer::from(calculate_similarity(lists()))
+        })
+        .expecting(25574739),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use crate::shared::file_parser::get_input;
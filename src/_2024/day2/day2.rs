@@ -1,3 +1,5 @@
+use crate::shared::{answer::Answer, file_parser::get_input, registry::DayPart};
+
 fn parse_input(input: String) -> Vec<Vec<u32>> {
     let mut res: Vec<Vec<u32>> = Vec::with_capacity(input.lines().count());
     for line in input.lines() {
@@ -78,6 +80,20 @@ fn count_valid_lists(lists: Vec<Vec<u32>>, allowed_unsafes: u32) -> usize {
         .count()
 }
 
+pub fn parts() -> Vec<DayPart> {
+    let lists = || parse_input(get_input(file!(), "input.txt"));
+    vec![
+        DayPart::new(2024, 2, 1, move || {
+            Answer::from(count_valid_lists(lists(), 0))
+        })
+        .expecting(257),
+        DayPart::new(2024, 2, 2, move || {
+            Answer::from(count_valid_lists(lists(), 1))
+        })
+        .expecting(328),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -1,5 +1,7 @@
 use regex::Regex;
 
+use crate::shared::{answer::Answer, file_parser::get_input, registry::DayPart};
+
 fn get_muls(input: String) -> Vec<(u64, u64)> {
     // regex that matches mul(43,425) or any mul(x,y) where x and y are numbers that can be any length and extracted into a tuple of u64
     let mul_regex = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap();
@@ -25,6 +27,20 @@ fn calculate_mul(input: Vec<(u64, u64)>) -> u64 {
     input.iter().map(|(x, y)| x * y).sum()
 }
 
+pub fn parts() -> Vec<DayPart> {
+    let input = || get_input(file!(), "input.txt");
+    vec![
+        DayPart::new(2024, 3, 1, move || {
+            Answer::from(calculate_mul(get_muls(input())))
+        })
+        .expecting(160672468),
+        DayPart::new(2024, 3, 2, move || {
+            Answer::from(calculate_mul(get_muls(remove_donts(input()))))
+        })
+        .expecting(84893551),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use crate::shared::file_parser::get_input;
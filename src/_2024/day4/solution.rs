@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use crate::shared::{answer::Answer, file_parser::get_input, registry::DayPart};
+
 type Coords = (i32, i32);
 type Grid = HashMap<Coords, char>;
 
@@ -117,6 +119,14 @@ fn has_char_on_same_side(grid: &Grid, a_coord: &Coords, char: char) -> bool {
         || (grid.get(&coord_left_down) == Some(&char) && grid.get(&coord_left_up) == Some(&char))
 }
 
+pub fn parts() -> Vec<DayPart> {
+    let grid = || parse_input(get_input(file!(), "input.txt"));
+    vec![
+        DayPart::new(2024, 4, 1, move || Answer::from(count_xmas_part1(&grid()))).expecting(2662),
+        DayPart::new(2024, 4, 2, move || Answer::from(count_xmas_part2(&grid()))).expecting(2034),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use crate::shared::file_parser::get_input;
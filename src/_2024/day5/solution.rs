@@ -2,6 +2,8 @@ use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 
+use crate::shared::{answer::Answer, file_parser::get_input, registry::DayPart};
+
 fn split_input(input: String) -> (String, String) {
     let mut split = input.split("\n\n");
     (
@@ -84,12 +86,88 @@ fn part2(page_ordering: HashMap<u32, HashSet<u32>>, page_numbers: Vec<Vec<u32>>)
     result
 }
 
+pub fn parts() -> Vec<DayPart> {
+    let input = || {
+        let (page_ordering_str, page_numbers_str) = split_input(get_input(file!(), "input.txt"));
+        (
+            page_order_index(page_ordering_str),
+            page_numbers(page_numbers_str),
+        )
+    };
+    vec![
+        DayPart::new(2024, 5, 1, move || {
+            let (page_ordering, page_numbers) = input();
+            Answer::from(part1(page_ordering, page_numbers).iter().sum::<u32>())
+        })
+        .expecting(4996),
+        DayPart::new(2024, 5, 2, move || {
+            let (page_ordering, page_numbers) = input();
+            Answer::from(part2(page_ordering, page_numbers).iter().sum::<u32>())
+        })
+        .expecting(6311),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::shared::file_parser::get_input;
+    use crate::shared::{
+        file_parser::get_input,
+        method_duration::log_method_duration,
+        random::{InputGenerator, Rng},
+    };
 
     use super::*;
 
+    // A hidden order of all pages with a rule for every pair, like the real input,
+    // about half of the updates already follow it
+    struct PageRulesGenerator {
+        pages: usize,
+        updates: usize,
+        max_update_length: usize,
+    }
+
+    impl Default for PageRulesGenerator {
+        fn default() -> Self {
+            Self {
+                pages: 49,
+                updates: 200,
+                max_update_length: 23,
+            }
+        }
+    }
+
+    impl InputGenerator for PageRulesGenerator {
+        fn generate(&self, rng: &mut Rng) -> String {
+            let mut order: Vec<usize> = (10..(10 + self.pages * 2).max(100)).collect();
+            rng.shuffle(&mut order);
+            order.truncate(self.pages);
+
+            let mut rules = Vec::new();
+            for (index, before) in order.iter().enumerate() {
+                for after in &order[index + 1..] {
+                    rules.push(format!("{}|{}", before, after));
+                }
+            }
+            rng.shuffle(&mut rules);
+
+            let max_length = self.max_update_length.min(self.pages);
+            let mut updates = Vec::new();
+            for _ in 0..self.updates {
+                // updates always have a middle page
+                let length = rng.range(1..max_length / 2 + 1) * 2 + 1;
+                let mut indexes: Vec<usize> = (0..self.pages).collect();
+                rng.shuffle(&mut indexes);
+                indexes.truncate(length.min(self.pages));
+                if rng.chance(1, 2) {
+                    indexes.sort();
+                }
+                let update: Vec<String> = indexes.iter().map(|i| order[*i].to_string()).collect();
+                updates.push(update.join(","));
+            }
+            format!("{}\n\n{}", rules.join("\n"), updates.join("\n"))
+        }
+    }
+
     #[test]
     fn example_1() {
         let input = get_input(file!(), "example.txt");
@@ -125,4 +203,58 @@ mod tests {
         let page_numbers = page_numbers(page_numbers_str);
         assert_eq!(6311u32, part2(page_ordering, page_numbers).iter().sum());
     }
+
+    // Every update is either valid or fixed by part 2, so together they hold
+    // the middle page of every update once it is sorted
+    fn check_generated(input: String) {
+        let (page_ordering_str, page_numbers_str) = split_input(input);
+        let page_ordering = page_order_index(page_ordering_str);
+        let page_numbers = page_numbers(page_numbers_str);
+        let rank = |page: &u32| page_ordering.get(page).map_or(0, |after| after.len());
+        let sorted_middles: u32 = page_numbers
+            .iter()
+            .map(|update| {
+                let mut sorted = update.clone();
+                sorted.sort_by_key(|page| std::cmp::Reverse(rank(page)));
+                sorted[sorted.len() / 2]
+            })
+            .sum();
+        let valid = part1(page_ordering.clone(), page_numbers.clone());
+        let fixed = part2(page_ordering, page_numbers.clone());
+        assert_eq!(page_numbers.len(), valid.len() + fixed.len());
+        assert_eq!(
+            sorted_middles,
+            valid.iter().sum::<u32>() + fixed.iter().sum::<u32>()
+        );
+    }
+
+    #[test]
+    fn generated_input() {
+        let generator = PageRulesGenerator::default();
+        assert_eq!(
+            generator.generate_with_seed(5),
+            generator.generate_with_seed(5)
+        );
+        for seed in 0..10 {
+            check_generated(generator.generate_with_seed(seed));
+        }
+        let small = PageRulesGenerator {
+            pages: 5,
+            updates: 50,
+            max_update_length: 5,
+        };
+        check_generated(small.generate_with_seed(5));
+    }
+
+    #[test]
+    #[ignore = "stress test, run with --ignored --nocapture"]
+    fn stress_generated_input() {
+        let generator = PageRulesGenerator {
+            pages: 500,
+            updates: 5_000,
+            max_update_length: 301,
+        };
+        let input = generator.generate_with_seed(5);
+        log_method_duration(|| check_generated(input));
+    }
 }
@@ -3,7 +3,20 @@ use std::{
     iter::Map,
 };
 
-use itertools::Itertools;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::{
+    shared::{
+        animation::{play, DayAnimation, Simulation},
+        answer::Answer,
+        cancellation::Deadline,
+        file_parser::get_input,
+        grid_renderer::{Arrow, GridRenderer},
+        image_export::{DayImage, GridImage, Rgb},
+        registry::DayPart,
+    },
+    trace,
+};
 
 type Coords = (i32, i32);
 
@@ -26,6 +39,15 @@ impl Direction {
         }
     }
 
+    fn arrow(&self) -> Arrow {
+        match self {
+            Direction::North => Arrow::Up,
+            Direction::East => Arrow::Right,
+            Direction::South => Arrow::Down,
+            Direction::West => Arrow::Left,
+        }
+    }
+
     #[inline]
     fn rotate(&self) -> Direction {
         match self {
@@ -105,14 +127,18 @@ fn find_guard(map: &HashMap<Coords, char>) -> Guard {
     panic!("No guard found in map");
 }
 
+// The deadline is passed in rather than read from the thread, the walks for
+// part two run on rayon workers which may be busy with another part
 fn traverse_map(
     map: &HashMap<Coords, char>,
     guard: &Guard,
     obstacle: Option<&Coords>,
+    deadline: Deadline,
 ) -> (Guard, bool) {
     let mut guard = guard.clone();
     let mut infinite_loop = false;
     loop {
+        deadline.checkpoint();
         infinite_loop = guard.walk(&map, obstacle);
         if infinite_loop {
             break;
@@ -120,7 +146,7 @@ fn traverse_map(
         if Option::None == map.get(&guard.coords) {
             break;
         }
-        // print_map(&map);
+        // print_map(map, Some(&guard));
     }
     (guard, infinite_loop)
 }
@@ -129,46 +155,244 @@ fn is_infinit_loop_when_adding_obstacle_at(
     guard: &Guard,
     map: &HashMap<Coords, char>,
     obstacle: &Coords,
+    deadline: Deadline,
 ) -> bool {
-    traverse_map(map, guard, Option::Some(obstacle)).1
+    traverse_map(map, guard, Option::Some(obstacle), deadline).1
 }
 
-fn print_map(map: &HashMap<Coords, char>) {
-    println!();
-    println!();
-    map.keys()
-        .sorted_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)))
-        .for_each(|coords| {
-            if coords.0 == 0 {
-                println!();
-            }
-            print!("{}", map.get(coords).unwrap());
-        });
+// Obstacles in black and every position the guard visited in yellow
+fn coverage_image<'a>(
+    map: &'a HashMap<Coords, char>,
+    guard: &'a Guard,
+) -> GridImage<impl Fn(usize, usize) -> Rgb + 'a> {
+    let width = map.keys().map(|coords| coords.0).max().unwrap_or(-1) + 1;
+    let height = map.keys().map(|coords| coords.1).max().unwrap_or(-1) + 1;
+    GridImage::new(width as usize, height as usize, |x, y| {
+        let coords = (x as i32, y as i32);
+        match map.get(&coords) {
+            Some('#') => Rgb::BLACK,
+            _ if guard.previous_positions.contains_key(&coords) => Rgb::YELLOW,
+            _ => Rgb::WHITE,
+        }
+    })
+}
+
+#[derive(Clone)]
+struct GuardWalk {
+    map: HashMap<Coords, char>,
+    guard: Guard,
+    finished: bool,
+}
+
+impl Simulation for GuardWalk {
+    fn step(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
+        let infinite_loop = self.guard.walk(&self.map, None);
+        self.finished = infinite_loop || !self.map.contains_key(&self.guard.coords);
+        true
+    }
+
+    fn frame(&self) -> String {
+        format!(
+            "{:#}\nVisited: {}",
+            render_map(&self.map, Some(&self.guard)),
+            self.guard.previous_positions.len()
+        )
+    }
+}
+
+fn print_map(map: &HashMap<Coords, char>, guard: Option<&Guard>) {
+    trace!(Trace, "\n{}", render_map(map, guard));
+}
+
+fn render_map<'a>(
+    map: &'a HashMap<Coords, char>,
+    guard: Option<&Guard>,
+) -> GridRenderer<impl Fn(usize, usize) -> char + 'a> {
+    let width = map.keys().map(|coords| coords.0).max().unwrap_or(-1) + 1;
+    let height = map.keys().map(|coords| coords.1).max().unwrap_or(-1) + 1;
+    let renderer = GridRenderer::new(width as usize, height as usize, |x, y| {
+        *map.get(&(x as i32, y as i32)).unwrap_or(&' ')
+    });
+    match guard {
+        Some(guard) => renderer
+            .with_visited(
+                guard
+                    .previous_positions
+                    .keys()
+                    .filter(|coords| map.contains_key(coords))
+                    .map(|coords| (coords.0 as usize, coords.1 as usize)),
+                Some('X'),
+            )
+            .with_actor(
+                (guard.coords.0 as usize, guard.coords.1 as usize),
+                Some(guard.direction.arrow()),
+            ),
+        None => renderer,
+    }
+}
+
+// Only positions the guard visits without a new obstacle can change its path,
+// the start itself is off limits as the guard is standing there. The
+// candidates are checked on rayon threads, each walk gets `deadline`.
+fn count_loop_obstacles(map: &HashMap<Coords, char>, guard: &Guard, deadline: Deadline) -> usize {
+    let visited = traverse_map(map, guard, Option::None, deadline)
+        .0
+        .previous_positions;
+    visited
+        .keys()
+        .filter(|obstacle| **obstacle != guard.coords)
+        .collect::<Vec<&Coords>>()
+        .par_iter()
+        .filter(|obstacle| is_infinit_loop_when_adding_obstacle_at(guard, map, obstacle, deadline))
+        .count()
+}
+
+pub fn parts() -> Vec<DayPart> {
+    vec![
+        DayPart::new(2024, 6, 1, move || {
+            let map = parse_input(get_input(file!(), "input.txt"));
+            let guard = find_guard(&map);
+            let final_guard = traverse_map(&map, &guard, Option::None, Deadline::current()).0;
+            Answer::from(final_guard.previous_positions.len())
+        })
+        .expecting(5129),
+        DayPart::new(2024, 6, 2, move || {
+            let map = parse_input(get_input(file!(), "input.txt"));
+            let guard = find_guard(&map);
+            Answer::from(count_loop_obstacles(&map, &guard, Deadline::current()))
+        })
+        .expecting(1888),
+    ]
+}
+
+// The guard walking the example map
+pub fn animation() -> DayAnimation {
+    DayAnimation::new(2024, 6, |config| {
+        let map = parse_input(get_input(file!(), "example.txt"));
+        let guard = find_guard(&map);
+        let walk = GuardWalk {
+            map,
+            guard,
+            finished: false,
+        };
+        play(walk, config);
+    })
+}
+
+// Every position the guard of part one visits on the real input
+pub fn image() -> DayImage {
+    DayImage::new(2024, 6, |path, config| {
+        let map = parse_input(get_input(file!(), "input.txt"));
+        let guard = find_guard(&map);
+        let final_guard = traverse_map(&map, &guard, Option::None, Deadline::current()).0;
+        let image = coverage_image(&map, &final_guard);
+        image.save(path, config)
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+    use std::time::Duration;
 
-    use crate::shared::file_parser::get_input;
+    use crate::shared::{
+        cancellation::{configured_budget, with_time_budget, TimedOut},
+        differential::Differential,
+        file_parser::get_input,
+        method_duration::log_method_duration,
+        random::{InputGenerator, Rng},
+    };
 
     use super::*;
 
+    // A map with randomly placed obstacles and the guard facing north. The cell in
+    // front of the guard is always free so it can't start boxed in.
+    struct GuardMapGenerator {
+        width: usize,
+        height: usize,
+        // chance out of 100 for a cell to hold an obstacle
+        obstacle_percentage: usize,
+    }
+
+    impl Default for GuardMapGenerator {
+        fn default() -> Self {
+            Self {
+                width: 130,
+                height: 130,
+                obstacle_percentage: 5,
+            }
+        }
+    }
+
+    impl InputGenerator for GuardMapGenerator {
+        fn generate(&self, rng: &mut Rng) -> String {
+            let mut rows: Vec<Vec<char>> = (0..self.height)
+                .map(|_| {
+                    (0..self.width)
+                        .map(|_| {
+                            if rng.chance(self.obstacle_percentage, 100) {
+                                '#'
+                            } else {
+                                '.'
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+            let x = rng.range(0..self.width);
+            let y = rng.range(0..self.height);
+            rows[y][x] = '^';
+            if y > 0 {
+                rows[y - 1][x] = '.';
+            }
+            rows.iter()
+                .map(|row| row.iter().collect::<String>())
+                .collect::<Vec<String>>()
+                .join("\n")
+        }
+    }
+
     #[test]
     fn example_1() {
         let input = get_input(file!(), "example.txt");
         let map = parse_input(input);
         let guard = find_guard(&map);
-        let final_guard = traverse_map(&map, &guard, Option::None).0;
+        let final_guard = traverse_map(&map, &guard, Option::None, Deadline::default()).0;
         assert_eq!(41, final_guard.previous_positions.len());
     }
 
+    #[test]
+    fn example_1_render() {
+        let input = get_input(file!(), "example.txt");
+        let map = parse_input(input);
+        let guard = find_guard(&map);
+        let final_guard = traverse_map(&map, &guard, Option::None, Deadline::default()).0;
+        assert_eq!(
+            [
+                "....#.....",
+                "....XXXXX#",
+                "....X...X.",
+                "..#.X...X.",
+                "..XXXXX#X.",
+                "..X.X.X.X.",
+                ".#XXXXXXX.",
+                ".XXXXXXX#.",
+                "#XXXXXXX..",
+                "......#X..",
+            ]
+            .join("\n"),
+            render_map(&map, Some(&final_guard)).to_string()
+        );
+    }
+
     #[test]
     fn input_1() {
         let input = get_input(file!(), "input.txt");
         let map = parse_input(input);
         let guard = find_guard(&map);
-        let final_guard = traverse_map(&map, &guard, Option::None).0;
+        let final_guard = traverse_map(&map, &guard, Option::None, Deadline::default()).0;
         assert_eq!(5129, final_guard.previous_positions.len());
     }
 
@@ -177,13 +401,18 @@ mod tests {
         let input = get_input(file!(), "example.txt");
         let map = parse_input(input);
         let guard = find_guard(&map);
-        let map_with_x = traverse_map(&map, &guard, Option::None);
+        let map_with_x = traverse_map(&map, &guard, Option::None, Deadline::default());
         let result = map_with_x
             .0
             .previous_positions
             .keys()
             .map(|obstacle| {
-                if is_infinit_loop_when_adding_obstacle_at(&guard, &map, obstacle) {
+                if is_infinit_loop_when_adding_obstacle_at(
+                    &guard,
+                    &map,
+                    obstacle,
+                    Deadline::default(),
+                ) {
                     1
                 } else {
                     0
@@ -198,18 +427,141 @@ mod tests {
         let input = get_input(file!(), "input.txt");
         let map = parse_input(input);
         let guard = find_guard(&map);
-        let map_with_x = traverse_map(&map, &guard, Option::None);
-        let obstacles: Vec<&Coords> = map_with_x.0.previous_positions.keys().collect();
-        let result = obstacles
-            .par_iter()
-            .map(|obstacle| {
-                if is_infinit_loop_when_adding_obstacle_at(&guard, &map, *obstacle) {
-                    1
+        let result = with_time_budget(configured_budget(), || {
+            count_loop_obstacles(&map, &guard, Deadline::current())
+        });
+        let result = result.unwrap();
+        assert_eq!(1888, result);
+    }
+
+    #[test]
+    fn input_2_stops_at_deadline() {
+        let map = parse_input(get_input(file!(), "input.txt"));
+        let guard = find_guard(&map);
+        let budget = Duration::from_millis(10);
+        let result = with_time_budget(Some(budget), || {
+            count_loop_obstacles(&map, &guard, Deadline::current())
+        });
+        assert_eq!(Err(TimedOut { budget }), result);
+    }
+
+    // Positions visited and obstacles that cause a loop, as the parts compute
+    // them
+    fn visited_and_loops(input: &str) -> (usize, usize) {
+        let map = parse_input(input.to_owned());
+        let guard = find_guard(&map);
+        let visited = traverse_map(&map, &guard, Option::None, Deadline::default())
+            .0
+            .previous_positions;
+        let loops = count_loop_obstacles(&map, &guard, Deadline::default());
+        (visited.len(), loops)
+    }
+
+    // Straightforward walk over the grid, a loop is a repeated position and
+    // direction
+    fn reference_visited_and_loops(input: &str) -> (usize, usize) {
+        let grid: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
+        let start = grid
+            .iter()
+            .enumerate()
+            .find_map(|(y, row)| {
+                row.iter()
+                    .position(|c| *c == '^')
+                    .map(|x| (x as i32, y as i32))
+            })
+            .unwrap();
+        let walk = |obstacle: Option<Coords>| {
+            let blocked = |(x, y): Coords| {
+                Some((x, y)) == obstacle
+                    || grid.get(y as usize).and_then(|row| row.get(x as usize)) == Some(&'#')
+            };
+            let mut seen = HashSet::new();
+            let (mut position, mut direction) = (start, Direction::North);
+            loop {
+                if !seen.insert((position, direction)) {
+                    return (seen, true);
+                }
+                let next = direction.get_next_coords(position);
+                if next.0 < 0
+                    || next.1 < 0
+                    || next.1 as usize >= grid.len()
+                    || next.0 as usize >= grid[0].len()
+                {
+                    return (seen, false);
+                }
+                if blocked(next) {
+                    direction = direction.rotate();
                 } else {
-                    0
+                    position = next;
                 }
-            })
-            .sum();
-        assert_eq!(1888, result);
+            }
+        };
+        let visited: HashSet<Coords> = walk(None).0.into_iter().map(|(p, _)| p).collect();
+        let loops = visited
+            .iter()
+            .filter(|obstacle| **obstacle != start && walk(Some(**obstacle)).1)
+            .count();
+        (visited.len(), loops)
+    }
+
+    #[test]
+    fn no_obstacle_on_the_start() {
+        // found by differential_generated_maps, an obstacle where the guard
+        // stands would make it loop
+        let input = [
+            "......#...#.",
+            "..........#.",
+            "..#...#...#.",
+            "...........#",
+            "..^.#......#",
+            "##....#.....",
+            "..#......#..",
+            ".#.......###",
+            "........#...",
+            ".#..........",
+            "...........#",
+            ".....#.#....",
+        ]
+        .join("\n");
+        assert_eq!(
+            reference_visited_and_loops(&input),
+            visited_and_loops(&input)
+        );
+    }
+
+    #[test]
+    fn differential_generated_maps() {
+        let differential = Differential::new(
+            "visited_and_loops",
+            |input: &String| visited_and_loops(input),
+            "reference_visited_and_loops",
+            |input| reference_visited_and_loops(input),
+        );
+        let generator = GuardMapGenerator {
+            width: 12,
+            height: 12,
+            obstacle_percentage: 15,
+        };
+        let examples = vec![get_input(file!(), "example.txt")];
+        if let Err(disagreement) = differential.check(examples) {
+            panic!("{}", disagreement);
+        }
+        if let Err(disagreement) = differential.check_random(6, 200, |rng| generator.generate(rng))
+        {
+            panic!("{}", disagreement);
+        }
+    }
+
+    #[test]
+    #[ignore = "stress test, run with --ignored --nocapture"]
+    fn stress_generated_map() {
+        let generator = GuardMapGenerator {
+            width: 400,
+            height: 400,
+            obstacle_percentage: 5,
+        };
+        let input = generator.generate_with_seed(6);
+        let (visited, loops) = log_method_duration(|| visited_and_loops(&input));
+        assert!(visited > 0 && loops <= visited);
     }
 }
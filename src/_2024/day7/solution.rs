@@ -1,3 +1,7 @@
+use crate::shared::{
+    answer::Answer, cancellation::checkpoint, file_parser::get_input, registry::DayPart,
+};
+
 fn parse_input(input: String) -> Vec<(u128, Vec<u128>)> {
     input
         .lines()
@@ -92,14 +96,17 @@ impl Operations {
 
 fn can_operations_combine_result(input: &(u128, Vec<u128>), allow_concat: bool) -> bool {
     let (result, numbers) = input;
-    if numbers.iter().any(|n| n >= &&result) {
-        return false;
-    }
-    if numbers.iter().filter(|n| *n == &1u128).sum::<u128>() > *result {
+    // no operation makes a positive number smaller, ones can still be
+    // multiplied away so they don't say anything about the result
+    if !numbers.contains(&0) && numbers.iter().any(|n| n > result) {
         return false;
     }
     let mut operations = Operations::new(numbers.len() - 1, allow_concat);
-    loop {
+    // there are 3^(n - 1) combinations with concat, look at the clock now and then
+    for combination in 0usize.. {
+        if combination % 4096 == 0 {
+            checkpoint();
+        }
         let mut tmp_result = numbers[0];
         for (index, operation) in operations.operations.iter().enumerate() {
             tmp_result = operation.apply(tmp_result, numbers[index + 1]);
@@ -119,7 +126,7 @@ fn can_operations_combine_result(input: &(u128, Vec<u128>), allow_concat: bool)
 // Old way of solving part 1
 fn can_operations_combine_result_old(input: &(u128, Vec<u128>)) -> bool {
     let (result, numbers) = input;
-    if numbers.iter().any(|n| n >= &&result) {
+    if !numbers.contains(&0) && numbers.iter().any(|n| n > result) {
         return false;
     }
     for operation_mix in 0..2usize.pow((numbers.len() - 1) as u32) {
@@ -132,17 +139,45 @@ fn can_operations_combine_result_old(input: &(u128, Vec<u128>)) -> bool {
                 1 => tmp_result *= numbers[number_index],
                 _ => unreachable!(),
             }
-            if &tmp_result == result {
-                return true;
-            }
+        }
+        // only a result that uses every number counts
+        if &tmp_result == result {
+            return true;
         }
     }
     return false;
 }
 
+fn calibration_result(equations: Vec<(u128, Vec<u128>)>, allow_concat: bool) -> u128 {
+    equations
+        .into_iter()
+        .filter(|equation| can_operations_combine_result(equation, allow_concat))
+        .map(|(result, _)| result)
+        .sum()
+}
+
+pub fn parts() -> Vec<DayPart> {
+    let equations = || parse_input(get_input(file!(), "input.txt"));
+    vec![
+        DayPart::new(2024, 7, 1, move || {
+            Answer::from(calibration_result(equations(), false))
+        })
+        .expecting(21572148763543u128),
+        DayPart::new(2024, 7, 2, move || {
+            Answer::from(calibration_result(equations(), true))
+        })
+        .expecting(581941094529163u128),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::shared::file_parser::get_input;
+    use crate::shared::{
+        cancellation::{configured_budget, with_time_budget},
+        differential::{shrink_number, shrink_vec, Differential},
+        file_parser::get_input,
+        random::Rng,
+    };
 
     use super::*;
 
@@ -249,13 +284,102 @@ mod tests {
     fn input_2() {
         let input = get_input(file!(), "input.txt");
         let parsed = parse_input(input);
-        assert_eq!(
-            581941094529163u128,
-            parsed
+        let result = with_time_budget(configured_budget(), || calibration_result(parsed, true));
+        assert_eq!(Ok(581941094529163u128), result);
+    }
+
+    fn part_1_differential() -> Differential<'static, (u128, Vec<u128>), bool> {
+        Differential::new(
+            "can_operations_combine_result",
+            |input| can_operations_combine_result(input, false),
+            "can_operations_combine_result_old",
+            can_operations_combine_result_old,
+        )
+        .with_shrinker(|(result, numbers)| {
+            let mut candidates: Vec<(u128, Vec<u128>)> = shrink_vec(numbers, 1)
                 .into_iter()
-                .filter(|i| can_operations_combine_result(i, true))
-                .map(|(result, _)| result)
-                .sum()
-        );
+                .map(|numbers| (*result, numbers))
+                .collect();
+            for smaller in shrink_number(*result, 1) {
+                candidates.push((smaller, numbers.clone()));
+            }
+            for (index, number) in numbers.iter().enumerate() {
+                for smaller in shrink_number(*number, 1) {
+                    let mut numbers = numbers.clone();
+                    numbers[index] = smaller;
+                    candidates.push((*result, numbers));
+                }
+            }
+            candidates
+        })
+    }
+
+    // Half of the equations are built from a random mix of operations so the
+    // result can be reached, the other half get a random result
+    // Numbers are mostly ones and other small values, those are where the
+    // shortcuts for impossible results go wrong
+    fn random_equation(rng: &mut Rng) -> (u128, Vec<u128>) {
+        let numbers: Vec<u128> = (0..rng.range(1..7))
+            .map(|_| match rng.range(0..3) {
+                0 => 1,
+                1 => rng.range(1..4) as u128,
+                _ => rng.range(1..20) as u128,
+            })
+            .collect();
+        let result =
+            if rng.chance(1, 2) {
+                numbers[1..].iter().fold(numbers[0], |acc, n| {
+                    if rng.chance(1, 2) {
+                        acc + n
+                    } else {
+                        acc * n
+                    }
+                })
+            } else if rng.chance(1, 2) {
+                rng.range(1..10) as u128
+            } else {
+                rng.range(1..2000) as u128
+            };
+        (result, numbers)
+    }
+
+    #[test]
+    fn old_ignores_partial_results() {
+        // found by differential_random_input, 4 + 19 already gave 23
+        assert!(!can_operations_combine_result_old(&(23, vec![4, 19, 2])));
+    }
+
+    #[test]
+    fn ones_can_be_multiplied_away() {
+        // the sum of the ones used to rule this out, 1 * 1 + 1 = 2
+        assert!(can_operations_combine_result(&(2, vec![1, 1, 1]), false));
+        assert!(can_operations_combine_result_old(&(2, vec![1, 1, 1])));
+    }
+
+    #[test]
+    fn number_equal_to_result() {
+        // both versions used to rule out any number as large as the result,
+        // which the differential tests can't notice as they agree: 1 * 6 = 6
+        assert!(can_operations_combine_result(&(6, vec![1, 6]), false));
+        assert!(can_operations_combine_result_old(&(6, vec![1, 6])));
+        assert!(can_operations_combine_result(&(6, vec![6]), true));
+        assert!(!can_operations_combine_result(&(6, vec![7, 1]), true));
+    }
+
+    #[test]
+    fn differential_real_input() {
+        let input = get_input(file!(), "input.txt");
+        let result = part_1_differential().check(parse_input(input));
+        if let Err(disagreement) = result {
+            panic!("{}", disagreement);
+        }
+    }
+
+    #[test]
+    fn differential_random_input() {
+        let result = part_1_differential().check_random(7, 2000, random_equation);
+        if let Err(disagreement) = result {
+            panic!("{}", disagreement);
+        }
     }
 }
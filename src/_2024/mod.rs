@@ -1,3 +1,5 @@
+use crate::shared::{animation::DayAnimation, image_export::DayImage, registry::DayPart};
+
 mod day1;
 mod day2;
 mod day3;
@@ -5,3 +7,29 @@ mod day4;
 mod day5;
 mod day6;
 mod day7;
+
+// Every part this year's solutions can run on the real input
+pub fn parts() -> Vec<DayPart> {
+    [
+        day1::day1::parts(),
+        day2::day2::parts(),
+        day3::day3::parts(),
+        day4::solution::parts(),
+        day5::solution::parts(),
+        day6::solution::parts(),
+        day7::solution::parts(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+// Every day that can be watched with the animate command
+pub fn animations() -> Vec<DayAnimation> {
+    vec![day6::solution::animation()]
+}
+
+// Every day that can be drawn with the export command
+pub fn images() -> Vec<DayImage> {
+    vec![day6::solution::image()]
+}
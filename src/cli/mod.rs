@@ -0,0 +1,191 @@
+use std::{fs, path::Path, process::ExitCode, str::FromStr, time::Duration};
+
+use crate::shared::{
+    animation::PlayerConfig,
+    cancellation::{configured_budget, parse_budget},
+    image_export::{ExportConfig, ImageFormat},
+    session::SessionClient,
+    trace::set_filter,
+};
+
+pub mod report;
+pub mod run;
+
+const USAGE: &str = "Usage:
+  adventofcode animate --year YEAR --day DAY [--delay-ms MS] [--start-frame N]
+  adventofcode export --year YEAR --day DAY [--format png|ppm|svg] [--scale PIXELS] --out FILE
+  adventofcode fetch --year YEAR --day DAY [--input FILE]
+  adventofcode report [--year YEAR] [--markdown FILE]
+  adventofcode run --all [--year YEAR] [--threads N] [--timeout SECONDS] [--trace FILTER]
+
+animate plays a day's simulation on its example in the terminal, the days
+that have one are 2023 day 14 and 16 and 2024 day 6.
+
+export draws a day's solution on its real input, the loop of 2023 day 10, the
+energized tiles of 2023 day 16, the path of 2023 day 17 and the positions the
+guard visits in 2024 day 6. The format defaults to the extension of --out,
+every cell is --scale pixels wide (default 5).
+
+fetch needs AOC_SESSION, it downloads the input to the assets of the day (as
+input.txt unless --input is given, an existing file is kept) and rewrites the
+README from the puzzle page.
+
+report reads the sources, a star is a part registered with its expected
+answer (nothing is run).
+
+Parts get AOC_TIMEOUT seconds (default 300) unless --timeout is given, 0 means
+no limit. --trace takes the same filter as AOC_TRACE (e.g. `debug` or
+`warn,2023::day10=trace`, which also draws the loop of day 10) and replaces
+it. Built with `--features alloc-stats` the allocations of every part are
+counted as well, the parts then run one at a time.";
+
+// Value of an option like `--year 2023`
+fn option<'a>(args: &'a [String], name: &str) -> Result<Option<&'a str>, String> {
+    match args.iter().position(|arg| arg == name) {
+        Some(index) => args
+            .get(index + 1)
+            .map(|value| Some(value.as_str()))
+            .ok_or(format!("{} needs a value", name)),
+        None => Ok(None),
+    }
+}
+
+fn year_option(args: &[String]) -> Result<Option<u16>, String> {
+    option(args, "--year")?
+        .map(|year| year.parse().map_err(|_| format!("Invalid year {}", year)))
+        .transpose()
+}
+
+// Value of a numeric option like `--delay-ms 50`
+fn number_option<T: FromStr>(args: &[String], name: &str) -> Result<Option<T>, String> {
+    option(args, name)?
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| format!("Invalid {} {}", name, value))
+        })
+        .transpose()
+}
+
+fn day_option(args: &[String]) -> Result<Option<u8>, String> {
+    match number_option(args, "--day")? {
+        Some(day) if !(1..=25).contains(&day) => Err("The day must be between 1 and 25".to_owned()),
+        day => Ok(day),
+    }
+}
+
+fn animate(args: &[String]) -> Result<ExitCode, String> {
+    let year = year_option(args)?.ok_or(USAGE.to_owned())?;
+    let day = day_option(args)?.ok_or(USAGE.to_owned())?;
+    let animation = crate::_2023::animations()
+        .into_iter()
+        .chain(crate::_2024::animations())
+        .find(|animation| (animation.year, animation.day) == (year, day))
+        .ok_or(format!("There is no animation for {} day {}", year, day))?;
+    let mut config = PlayerConfig::default();
+    if let Some(delay) = number_option(args, "--delay-ms")? {
+        config.delay = Duration::from_millis(delay);
+    }
+    if let Some(start_frame) = number_option(args, "--start-frame")? {
+        config.start_frame = start_frame;
+    }
+    animation.play(config);
+    Ok(ExitCode::SUCCESS)
+}
+
+fn export(args: &[String]) -> Result<ExitCode, String> {
+    let year = year_option(args)?.ok_or(USAGE.to_owned())?;
+    let day = day_option(args)?.ok_or(USAGE.to_owned())?;
+    let out = Path::new(option(args, "--out")?.ok_or(USAGE.to_owned())?);
+    let format = match option(args, "--format")? {
+        Some(format) => format.parse()?,
+        None => ImageFormat::from_path(out).ok_or(format!(
+            "No --format given and {} is not a .png, .ppm or .svg file",
+            out.display()
+        ))?,
+    };
+    let mut config = ExportConfig::new(format);
+    if let Some(scale) = number_option(args, "--scale")? {
+        config.scale = scale;
+    }
+    let image = crate::_2023::images()
+        .into_iter()
+        .chain(crate::_2024::images())
+        .find(|image| (image.year, image.day) == (year, day))
+        .ok_or(format!("There is no image for {} day {}", year, day))?;
+    image.save(out, &config).map_err(|e| e.to_string())?;
+    println!("Image written to {}", out.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+fn fetch(args: &[String]) -> Result<ExitCode, String> {
+    let year = year_option(args)?.ok_or(USAGE.to_owned())?;
+    let day = day_option(args)?.ok_or(USAGE.to_owned())?;
+    let client = SessionClient::from_env().map_err(|e| e.to_string())?;
+    let input = option(args, "--input")?.unwrap_or("input.txt");
+    let path = client
+        .download_input(year, day, input)
+        .map_err(|e| e.to_string())?;
+    println!("Input in {}", path.display());
+    let path = client
+        .download_readme(year, day)
+        .map_err(|e| e.to_string())?;
+    println!("README written to {}", path.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+fn report(args: &[String]) -> Result<ExitCode, String> {
+    let mut years = report::discover(&report::source_directory()).map_err(|e| e.to_string())?;
+    let year = year_option(args)?;
+    if let Some(year) = year {
+        years.retain(|report| report.year == year);
+    }
+    report::mark_solved(&mut years, &run::registered_parts(year));
+    print!("{}", report::render_terminal(&years));
+    let markdown = option(args, "--markdown")?.unwrap_or("target/report.md");
+    if let Some(parent) = Path::new(markdown).parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(markdown, report::render_markdown(&years)).map_err(|e| e.to_string())?;
+    println!("Markdown report written to {}", markdown);
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run(args: &[String]) -> Result<ExitCode, String> {
+    if !args.iter().any(|arg| arg == "--all") {
+        return Err(USAGE.to_owned());
+    }
+    let threads = option(args, "--threads")?
+        .map(|threads| {
+            threads
+                .parse()
+                .map_err(|_| format!("Invalid number of threads {}", threads))
+        })
+        .transpose()?;
+    if let Some(spec) = option(args, "--trace")? {
+        set_filter(spec)?;
+    }
+    let budget = match option(args, "--timeout")? {
+        Some(seconds) => parse_budget(seconds)?,
+        None => configured_budget(),
+    };
+    match run::run_all(year_option(args)?, threads, budget)? {
+        true => Ok(ExitCode::SUCCESS),
+        false => Ok(ExitCode::FAILURE),
+    }
+}
+
+pub fn main(args: Vec<String>) -> ExitCode {
+    let result = match args.first().map(|command| command.as_str()) {
+        Some("animate") => animate(&args[1..]),
+        Some("export") => export(&args[1..]),
+        Some("fetch") => fetch(&args[1..]),
+        Some("report") => report(&args[1..]),
+        Some("run") => run(&args[1..]),
+        _ => Err(USAGE.to_owned()),
+    };
+    result.unwrap_or_else(|message| {
+        eprintln!("{}", message);
+        ExitCode::FAILURE
+    })
+}
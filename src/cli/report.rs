@@ -0,0 +1,358 @@
+use std::{
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::shared::registry::DayPart;
+
+const DAYS: u8 = 25;
+
+// What the repository holds for one day, found by reading the source files
+#[derive(Debug, Default, PartialEq)]
+pub struct DayReport {
+    pub day: u8,
+    // a part is solved when it is registered with its expected answer, having
+    // a test on the real input isn't enough as the test may fail
+    pub solved: [bool; 2],
+    pub example_test: bool,
+    pub real_test: bool,
+    pub readme: bool,
+}
+
+impl DayReport {
+    fn stars(&self) -> String {
+        self.solved
+            .iter()
+            .map(|solved| if *solved { '★' } else { '☆' })
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct YearReport {
+    pub year: u16,
+    pub days: Vec<DayReport>,
+}
+
+impl YearReport {
+    pub fn stars(&self) -> usize {
+        self.days
+            .iter()
+            .map(|day| day.solved.iter().filter(|s| **s).count())
+            .sum()
+    }
+
+    // Every day of the calendar, the ones without a module are empty
+    fn calendar(&self) -> Vec<DayReport> {
+        (1..=DAYS)
+            .map(|day| {
+                self.days
+                    .iter()
+                    .find(|report| report.day == day)
+                    .map(|report| DayReport { ..*report })
+                    .unwrap_or(DayReport {
+                        day,
+                        ..Default::default()
+                    })
+            })
+            .collect()
+    }
+}
+
+// Reads the tests of a source file, ignored tests don't count. This scans the
+// text as rustfmt lays it out: a test starts at `#[test]` and its body ends at
+// the first `}` indented by four spaces, so it only finds tests one module
+// deep and misreads a test with such a line inside its body (a multi-line
+// string for instance).
+fn scan_tests(source: &str, report: &mut DayReport) {
+    for test in source.split("#[test]").skip(1) {
+        let header = test.split('{').next().unwrap_or("");
+        if header.contains("#[ignore") {
+            continue;
+        }
+        let name = match header.split("fn ").nth(1) {
+            Some(rest) => rest.split('(').next().unwrap_or("").trim(),
+            None => continue,
+        };
+        // the body of the test runs up to the next item at module level
+        let body = test.split("\n    }\n").next().unwrap_or(test);
+        // some days have their input written out in the test instead of an asset
+        let example = name.contains("example") || body.contains("\"example");
+        let real = body.contains("\"input")
+            || (!example
+                && ["solution", "input", "answer"]
+                    .iter()
+                    .any(|w| name.contains(w)));
+        if example {
+            report.example_test = true;
+        }
+        if real {
+            report.real_test = true;
+        }
+    }
+}
+
+fn scan_day(day: u8, directory: &Path) -> io::Result<DayReport> {
+    let mut report = DayReport {
+        day,
+        readme: fs::metadata(directory.join("README.md")).is_ok_and(|m| m.len() > 0),
+        ..Default::default()
+    };
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "rs") {
+            scan_tests(&fs::read_to_string(path)?, &mut report);
+        }
+    }
+    Ok(report)
+}
+
+fn number_after(name: &str, prefix: &str) -> Option<u16> {
+    name.strip_prefix(prefix)
+        .and_then(|number| number.parse().ok())
+}
+
+// Finds src/_<year>/day<n> directories
+pub fn discover(source_directory: &Path) -> io::Result<Vec<YearReport>> {
+    let mut years = Vec::new();
+    for entry in fs::read_dir(source_directory)? {
+        let entry = entry?;
+        let year = match number_after(&entry.file_name().to_string_lossy(), "_") {
+            Some(year) if entry.path().is_dir() => year,
+            _ => continue,
+        };
+        let mut days = Vec::new();
+        for day_entry in fs::read_dir(entry.path())? {
+            let day_entry = day_entry?;
+            match number_after(&day_entry.file_name().to_string_lossy(), "day") {
+                Some(day) if day_entry.path().is_dir() => {
+                    days.push(scan_day(day as u8, &day_entry.path())?)
+                }
+                _ => continue,
+            }
+        }
+        days.sort_by_key(|day| day.day);
+        years.push(YearReport { year, days });
+    }
+    years.sort_by_key(|year| year.year);
+    Ok(years)
+}
+
+// Stars for the parts registered with their expected answer, the answers are
+// only stored once they are accepted so nothing has to run
+pub fn mark_solved(years: &mut [YearReport], parts: &[DayPart]) {
+    for part in parts.iter().filter(|part| part.expected.is_some()) {
+        let day = years
+            .iter_mut()
+            .filter(|year| year.year == part.year)
+            .flat_map(|year| year.days.iter_mut())
+            .find(|day| day.day == part.day);
+        if let Some(day) = day {
+            day.solved[part.part as usize - 1] = true;
+        }
+    }
+}
+
+fn mark(present: bool) -> &'static str {
+    if present {
+        "yes"
+    } else {
+        "-"
+    }
+}
+
+pub fn render_terminal(years: &[YearReport]) -> String {
+    let mut table = String::new();
+    for year in years {
+        writeln!(table, "{}  {}/{} stars", year.year, year.stars(), DAYS * 2).unwrap();
+        writeln!(table, "Day  Stars  Example  Input  README").unwrap();
+        for day in year.calendar() {
+            writeln!(
+                table,
+                "{:>3}  {:<5}  {:<7}  {:<5}  {}",
+                day.day,
+                day.stars(),
+                mark(day.example_test),
+                mark(day.real_test),
+                mark(day.readme)
+            )
+            .unwrap();
+        }
+        writeln!(table).unwrap();
+    }
+    table
+}
+
+pub fn render_markdown(years: &[YearReport]) -> String {
+    let mut markdown = String::from("# Progress\n");
+    for year in years {
+        writeln!(
+            markdown,
+            "\n## {} ({}/{} stars)\n",
+            year.year,
+            year.stars(),
+            DAYS * 2
+        )
+        .unwrap();
+        writeln!(
+            markdown,
+            "| Day | Stars | Example test | Real test | README |"
+        )
+        .unwrap();
+        writeln!(markdown, "| --: | :---: | :---: | :---: | :---: |").unwrap();
+        for day in year.calendar() {
+            writeln!(
+                markdown,
+                "| {} | {} | {} | {} | {} |",
+                day.day,
+                day.stars(),
+                mark(day.example_test),
+                mark(day.real_test),
+                mark(day.readme)
+            )
+            .unwrap();
+        }
+    }
+    markdown
+}
+
+pub fn source_directory() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shared::answer::Answer;
+
+    use super::*;
+
+    // A source tree with a solved 2023 day 1 that has a README, a 2023 day 17
+    // with only a test on the real input, a 2024 day 3 and a directory that
+    // is not a year
+    fn fixture() -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("report-{}", std::process::id()));
+        let files = [
+            (
+                "_2023/day1/day_one.rs",
+                "    #[test]\n    fn example_1() {\n        get_input(file!(), \"example.txt\");\n    }\n",
+            ),
+            ("_2023/day1/README.md", "--- Day 1: Test ---\n"),
+            (
+                "_2023/day17/day_seventeen.rs",
+                "    #[test]\n    fn solution_1_test() {\n        get_input(file!(), \"input1.txt\");\n    }\n",
+            ),
+            ("_2023/day17/README.md", ""),
+            ("_2024/day3/solution.rs", ""),
+            ("shared/mod.rs", ""),
+        ];
+        for (file, content) in files {
+            let path = directory.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        directory
+    }
+
+    #[test]
+    fn test_scan_tests() {
+        let source = r#"
+    #[test]
+    fn example_1() {
+        let input = get_input(file!(), "example.txt");
+    }
+
+    #[test]
+    #[ignore = "slow"]
+    fn input_2() {
+        let input = get_input(file!(), "input.txt");
+    }
+
+    #[test]
+    fn input_1() {
+        let input = get_input(file!(), "input.txt");
+    }
+}
+"#;
+        let mut report = DayReport::default();
+        scan_tests(source, &mut report);
+        assert_eq!([false, false], report.solved);
+        assert!(report.example_test && report.real_test);
+    }
+
+    #[test]
+    fn test_discover() {
+        let directory = fixture();
+        let mut years = discover(&directory).unwrap();
+        fs::remove_dir_all(directory).unwrap();
+        assert_eq!(
+            vec![2023, 2024],
+            years.iter().map(|year| year.year).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                DayReport {
+                    day: 1,
+                    example_test: true,
+                    readme: true,
+                    ..Default::default()
+                },
+                DayReport {
+                    day: 17,
+                    real_test: true,
+                    ..Default::default()
+                },
+            ],
+            years[0].days
+        );
+        assert_eq!(
+            vec![DayReport {
+                day: 3,
+                ..Default::default()
+            }],
+            years[1].days
+        );
+
+        mark_solved(
+            &mut years,
+            &[
+                DayPart::new(2023, 1, 1, || Answer::from(1)).expecting(1),
+                DayPart::new(2023, 1, 2, || Answer::from(2)).expecting(2),
+            ],
+        );
+        let markdown = render_markdown(&years);
+        assert!(markdown.contains("## 2023 (2/50 stars)"));
+        assert!(markdown.contains("| 1 | ★★ | yes | - | yes |"));
+        assert!(markdown.contains("| 17 | ☆☆ | - | yes | - |"));
+        assert!(markdown.contains("| 25 | ☆☆ | - | - | - |"));
+    }
+
+    #[test]
+    fn test_mark_solved() {
+        let mut years = vec![YearReport {
+            year: 2023,
+            days: vec![
+                DayReport {
+                    day: 1,
+                    ..Default::default()
+                },
+                DayReport {
+                    day: 17,
+                    real_test: true,
+                    ..Default::default()
+                },
+            ],
+        }];
+        let parts = vec![
+            DayPart::new(2023, 1, 1, || Answer::from(1)).expecting(1),
+            DayPart::new(2023, 1, 2, || Answer::from(2)),
+            // a test on the real input but no known answer
+            DayPart::new(2023, 17, 1, || Answer::from(102)),
+            DayPart::new(2024, 1, 1, || Answer::from(1)).expecting(1),
+        ];
+        mark_solved(&mut years, &parts);
+        assert_eq!("★☆", years[0].days[0].stars());
+        assert_eq!("☆☆", years[0].days[1].stars());
+        assert_eq!(1, years[0].stars());
+    }
+}
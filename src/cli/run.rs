@@ -0,0 +1,155 @@
+use std::{fmt::Write as _, time::Duration};
+
+use crate::shared::{
+    allocation::{self, format_bytes},
+    registry::{run_parts, DayPart, PartResult, Status},
+};
+
+// Every part the years register, in calendar order
+pub fn registered_parts(year: Option<u16>) -> Vec<DayPart> {
+    crate::_2023::parts()
+        .into_iter()
+        .chain(crate::_2024::parts())
+        .filter(|part| year.is_none_or(|year| part.year == year))
+        .collect()
+}
+
+fn status_text(status: &Status) -> String {
+    match status {
+        Status::Correct => String::from("ok"),
+        Status::Unknown => String::from("?"),
+        Status::Wrong => String::from("WRONG"),
+        Status::Panicked(message) => format!("PANIC {}", message),
+        Status::TimedOut(budget) => format!("TIMEOUT after {}", format_duration(*budget)),
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    if duration.as_secs() > 0 {
+        format!("{:.2}s", duration.as_secs_f64())
+    } else {
+        format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+    }
+}
+
+pub fn render_summary(results: &[PartResult]) -> String {
+    let mut table = String::new();
+    let with_allocations = results.iter().any(|result| result.allocations.is_some());
+    write!(
+        table,
+        "Year  Day  Part  {:<18}  {:<18}  {:>9}  ",
+        "Answer", "Expected", "Time"
+    )
+    .unwrap();
+    if with_allocations {
+        write!(
+            table,
+            "{:>10}  {:>10}  {:>10}  ",
+            "Allocs", "Allocated", "Peak"
+        )
+        .unwrap();
+    }
+    writeln!(table, "Status").unwrap();
+    for result in results {
+        let expected = result
+            .part
+            .expected
+            .as_ref()
+            .map_or(String::from("-"), |answer| answer.to_string());
+        write!(
+            table,
+            "{}  {:>3}  {:>4}  {:<18}  {:<18}  {:>9}  ",
+            result.part.year,
+            result.part.day,
+            result.part.part,
+            result.answer.to_string(),
+            expected,
+            format_duration(result.duration)
+        )
+        .unwrap();
+        if let Some(stats) = result.allocations {
+            write!(
+                table,
+                "{:>10}  {:>10}  {:>10}  ",
+                stats.allocations,
+                format_bytes(stats.bytes),
+                format_bytes(stats.peak_bytes)
+            )
+            .unwrap();
+        }
+        writeln!(table, "{}", status_text(&result.status)).unwrap();
+    }
+    let count = |f: fn(&Status) -> bool| results.iter().filter(|r| f(&r.status)).count();
+    let total: Duration = results.iter().map(|result| result.duration).sum();
+    writeln!(
+        table,
+        "\n{} parts: {} ok, {} unknown, {} regressed, {} of solving",
+        results.len(),
+        count(|status| *status == Status::Correct),
+        count(|status| *status == Status::Unknown),
+        count(Status::is_regression),
+        format_duration(total)
+    )
+    .unwrap();
+    table
+}
+
+// Runs the parts on a pool of `threads` threads, or as many as there are
+// cores, giving each part `budget` to finish
+pub fn run_in_pool(
+    parts: &[DayPart],
+    threads: Option<usize>,
+    budget: Option<Duration>,
+) -> Result<Vec<PartResult<'_>>, String> {
+    // the allocation counters are global, parts can't share them
+    let threads = if allocation::ENABLED {
+        Some(1)
+    } else {
+        threads
+    };
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads.unwrap_or(0))
+        .build()
+        .map_err(|e| e.to_string())?;
+    // every part catches its own panic, the default hook still prints where it
+    // happened before the table is written
+    Ok(pool.install(|| run_parts(parts, budget)))
+}
+
+pub fn run_all(
+    year: Option<u16>,
+    threads: Option<usize>,
+    budget: Option<Duration>,
+) -> Result<bool, String> {
+    let parts = registered_parts(year);
+    let results = run_in_pool(&parts, threads, budget)?;
+    print!("{}", render_summary(&results));
+    Ok(!results.iter().any(|result| result.status.is_regression()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shared::answer::Answer;
+
+    use super::*;
+
+    #[test]
+    fn test_registered_parts() {
+        let parts = registered_parts(Some(2024));
+        assert_eq!(14, parts.len());
+        assert!(parts.iter().all(|part| part.expected.is_some()));
+        let parts = registered_parts(None);
+        assert!(parts.iter().any(|part| (part.year, part.day) == (2023, 17)));
+    }
+
+    #[test]
+    fn test_render_summary() {
+        let parts = vec![
+            DayPart::new(2024, 1, 1, || Answer::from(11)).expecting(11),
+            DayPart::new(2024, 1, 2, || Answer::from(30)).expecting(31),
+        ];
+        let summary = render_summary(&run_parts(&parts, None));
+        assert!(summary.contains("WRONG"));
+        assert!(summary.contains("2 parts: 1 ok, 0 unknown, 1 regressed"));
+    }
+}
@@ -1,5 +1,6 @@
 mod _2023;
 mod _2024;
+pub mod cli;
 // mod day18;
 // mod day19;
 // mod day20;
@@ -0,0 +1,5 @@
+use std::{env, process::ExitCode};
+
+fn main() -> ExitCode {
+    adventofcode::cli::main(env::args().skip(1).collect())
+}
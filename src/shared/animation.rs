@@ -0,0 +1,272 @@
+use std::{
+    io::{self, BufRead, Write},
+    str::FromStr,
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+// A simulation that can be watched step by step in the terminal
+pub trait Simulation {
+    // Advances the simulation by one step, false once it has finished
+    fn step(&mut self) -> bool;
+
+    fn frame(&self) -> String;
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Step,
+    TogglePause,
+    Goto(usize),
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s {
+            "" | "s" => Ok(Command::Step),
+            "p" => Ok(Command::TogglePause),
+            "q" => Ok(Command::Quit),
+            _ => s
+                .trim_start_matches('g')
+                .trim()
+                .parse()
+                .map(Command::Goto)
+                .map_err(|_| format!("Unknown command {}", s)),
+        }
+    }
+}
+
+// Frames are rendered from the simulation when they are shown. Going back
+// replays the simulation from a copy of its first state, so only the first and
+// the current state are kept however long the animation runs.
+pub struct Animation<S: Simulation + Clone> {
+    start: S,
+    simulation: S,
+    current: usize,
+    // the number of the last frame, known once the simulation has finished
+    last: Option<usize>,
+}
+
+impl<S: Simulation + Clone> Animation<S> {
+    pub fn new(simulation: S) -> Self {
+        Self {
+            start: simulation.clone(),
+            simulation,
+            current: 0,
+            last: None,
+        }
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn frame(&self) -> String {
+        self.simulation.frame()
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.last == Some(self.current)
+    }
+
+    // Moves to frame n, or to the last frame when the simulation ends earlier
+    pub fn goto(&mut self, n: usize) {
+        let n = self.last.map_or(n, |last| n.min(last));
+        if n < self.current {
+            self.simulation = self.start.clone();
+            self.current = 0;
+        }
+        while self.current < n {
+            if !self.simulation.step() {
+                self.last = Some(self.current);
+                return;
+            }
+            self.current += 1;
+        }
+    }
+
+    pub fn next(&mut self) -> bool {
+        let previous = self.current;
+        self.goto(self.current + 1);
+        self.current != previous
+    }
+}
+
+pub struct PlayerConfig {
+    pub delay: Duration,
+    pub start_frame: usize,
+    pub paused: bool,
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        let delay = std::env::var("AOC_FRAME_DELAY_MS")
+            .ok()
+            .and_then(|ms| ms.parse().ok())
+            .unwrap_or(100);
+        Self {
+            delay: Duration::from_millis(delay),
+            start_frame: 0,
+            paused: false,
+        }
+    }
+}
+
+fn read_commands() -> Receiver<Command> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines().map_while(Result::ok) {
+            match line.parse() {
+                Ok(command) => {
+                    if sender.send(command).is_err() {
+                        break;
+                    }
+                }
+                Err(err) => eprintln!("{}", err),
+            }
+        }
+    });
+    receiver
+}
+
+fn draw<S: Simulation + Clone>(animation: &Animation<S>, paused: bool) {
+    let mut stdout = io::stdout().lock();
+    let state = match (paused, animation.is_last()) {
+        (_, true) => "finished",
+        (true, false) => "paused",
+        (false, false) => "playing",
+    };
+    // clear the screen and move the cursor to the top left
+    write!(stdout, "\x1b[2J\x1b[H").unwrap();
+    writeln!(stdout, "{}", animation.frame()).unwrap();
+    writeln!(
+        stdout,
+        "frame {} ({}) [enter] step, p pause/resume, g N goto frame N, q quit",
+        animation.current(),
+        state
+    )
+    .unwrap();
+    stdout.flush().unwrap();
+}
+
+// Plays the simulation in the terminal, commands are read line by line from
+// stdin so they have to be confirmed with enter
+pub fn play<S: Simulation + Clone>(simulation: S, config: PlayerConfig) {
+    let commands = read_commands();
+    let mut animation = Animation::new(simulation);
+    animation.goto(config.start_frame);
+    let mut paused = config.paused;
+    loop {
+        draw(&animation, paused);
+        let command = if paused || animation.is_last() {
+            match commands.recv() {
+                Ok(command) => Some(command),
+                Err(_) => return,
+            }
+        } else {
+            match commands.recv_timeout(config.delay) {
+                Ok(command) => Some(command),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => {
+                    thread::sleep(config.delay);
+                    None
+                }
+            }
+        };
+        match command {
+            Some(Command::Quit) => return,
+            Some(Command::TogglePause) => paused = !paused,
+            Some(Command::Step) => {
+                paused = true;
+                animation.next();
+            }
+            Some(Command::Goto(n)) => {
+                paused = true;
+                animation.goto(n);
+            }
+            None => {
+                animation.next();
+            }
+        }
+    }
+}
+
+// A day that can be watched with the animate command, it plays the simulation
+// on one of the day's example inputs
+pub struct DayAnimation {
+    pub year: u16,
+    pub day: u8,
+    play: Box<dyn Fn(PlayerConfig)>,
+}
+
+impl DayAnimation {
+    pub fn new(year: u16, day: u8, play: impl Fn(PlayerConfig) + 'static) -> Self {
+        Self {
+            year,
+            day,
+            play: Box::new(play),
+        }
+    }
+
+    pub fn play(&self, config: PlayerConfig) {
+        (self.play)(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone)]
+    struct Counter {
+        value: usize,
+        max: usize,
+    }
+
+    impl Simulation for Counter {
+        fn step(&mut self) -> bool {
+            if self.value == self.max {
+                return false;
+            }
+            self.value += 1;
+            true
+        }
+
+        fn frame(&self) -> String {
+            self.value.to_string()
+        }
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(Ok(Command::Step), "".parse());
+        assert_eq!(Ok(Command::TogglePause), "p".parse());
+        assert_eq!(Ok(Command::Goto(12)), "g 12".parse());
+        assert_eq!(Ok(Command::Goto(3)), "3".parse());
+        assert_eq!(Ok(Command::Quit), " q ".parse());
+        assert!("x".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_goto_and_next() {
+        let mut animation = Animation::new(Counter { value: 0, max: 5 });
+        assert_eq!("0", animation.frame());
+        animation.goto(3);
+        assert_eq!("3", animation.frame());
+        animation.goto(1);
+        assert_eq!("1", animation.frame());
+        animation.goto(100);
+        assert_eq!("5", animation.frame());
+        assert_eq!(5, animation.current());
+        assert!(animation.is_last());
+        assert!(!animation.next());
+        animation.goto(2);
+        assert_eq!("2", animation.frame());
+        assert!(!animation.is_last());
+    }
+}
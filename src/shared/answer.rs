@@ -0,0 +1,238 @@
+use std::{fmt, str::FromStr};
+
+use super::ocr::{self, OcrError};
+
+// A puzzle answer, whatever type the solution happens to return. Answers of
+// different variants are never equal, only the comparison with an expected
+// answer stored as text goes by what the answer prints.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+    Multiple(Vec<Answer>),
+    Unsolved,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{}", number),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Multiple(answers) => {
+                for (index, answer) in answers.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", answer)?;
+                }
+                Ok(())
+            }
+            Answer::Unsolved => write!(f, "-"),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() || s == "-" {
+            return Ok(Answer::Unsolved);
+        }
+        if s.contains(',') {
+            return Ok(Answer::Multiple(
+                s.split(',')
+                    .map(|part| {
+                        let Ok(answer) = part.parse();
+                        answer
+                    })
+                    .collect(),
+            ));
+        }
+        Ok(match s.parse::<i128>() {
+            Ok(number) => Answer::Number(number),
+            Err(_) => Answer::Text(s.to_owned()),
+        })
+    }
+}
+
+// Checks the Display output against `expected` piece by piece as it is
+// written, so comparing with a stored answer builds no string
+struct DisplayMatcher<'a> {
+    rest: &'a str,
+}
+
+impl fmt::Write for DisplayMatcher<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.rest = self.rest.strip_prefix(s).ok_or(fmt::Error)?;
+        Ok(())
+    }
+}
+
+fn displays_as(value: &impl fmt::Display, expected: &str) -> bool {
+    use fmt::Write;
+    let mut matcher = DisplayMatcher { rest: expected };
+    write!(matcher, "{}", value).is_ok() && matcher.rest.is_empty()
+}
+
+impl Answer {
+    // The letters drawn in the AoC fonts, for solvers whose answer is a drawing
+    pub fn from_drawing(drawing: &str) -> Result<Answer, OcrError> {
+        ocr::recognise(drawing).map(Answer::Text)
+    }
+
+    // Same as comparing with Answer::from(integer), `number` being the integer
+    // when it fits an i128
+    fn equals_integer(&self, number: Option<i128>, integer: &impl fmt::Display) -> bool {
+        match self {
+            Answer::Number(value) => Some(*value) == number,
+            Answer::Text(text) => displays_as(integer, text),
+            Answer::Multiple(answers) => {
+                answers.len() == 1 && answers[0].equals_integer(number, integer)
+            }
+            Answer::Unsolved => false,
+        }
+    }
+}
+
+macro_rules! answer_from_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl From<$integer> for Answer {
+                fn from(value: $integer) -> Self {
+                    Answer::Number(value as i128)
+                }
+            }
+
+            impl PartialEq<$integer> for Answer {
+                fn eq(&self, other: &$integer) -> bool {
+                    self.equals_integer(Some(*other as i128), other)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        match i128::try_from(value) {
+            Ok(number) => Answer::Number(number),
+            Err(_) => Answer::Text(value.to_string()),
+        }
+    }
+}
+
+impl PartialEq<u128> for Answer {
+    fn eq(&self, other: &u128) -> bool {
+        self.equals_integer(i128::try_from(*other).ok(), other)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::from(value.to_owned())
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        displays_as(self, other.trim())
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => value.into(),
+            None => Answer::Unsolved,
+        }
+    }
+}
+
+impl<T: Into<Answer>> From<Vec<T>> for Answer {
+    fn from(values: Vec<T>) -> Self {
+        Answer::Multiple(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<A: Into<Answer>, B: Into<Answer>> From<(A, B)> for Answer {
+    fn from((a, b): (A, B)) -> Self {
+        Answer::Multiple(vec![a.into(), b.into()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(Answer::Number(55029), Answer::from(55029i32));
+        assert_eq!(Answer::from(-5isize), Answer::from(-5i64));
+        assert_eq!(Answer::from(7u32), Answer::from(7usize));
+        assert_eq!(Answer::Unsolved, Answer::from(None::<usize>));
+        assert_eq!(Answer::Number(102), Answer::from(Some(102usize)));
+        assert_eq!(Answer::Text(u128::MAX.to_string()), Answer::from(u128::MAX));
+        assert_eq!(Answer::Text(String::from("ABC")), Answer::from("ABC"));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            "581941094529163",
+            Answer::from(581941094529163u128).to_string()
+        );
+        assert_eq!("HE", Answer::from(String::from("HE")).to_string());
+        assert_eq!("1,2,3", Answer::from(vec![1, 2, 3]).to_string());
+        assert_eq!("8,ok", Answer::from((8u64, "ok")).to_string());
+        assert_eq!("-", Answer::Unsolved.to_string());
+    }
+
+    #[test]
+    fn test_drawn_letters() {
+        let drawing = "#..#.####\n#..#.#...\n####.###.\n#..#.#...\n#..#.#...\n#..#.####";
+        assert_eq!(
+            Ok(Answer::Text(String::from("HE"))),
+            Answer::from_drawing(drawing)
+        );
+        assert!(Answer::from_drawing("#\n#").is_err());
+        // text is never read as a drawing
+        assert_eq!(Answer::Text(drawing.to_owned()), Answer::from(drawing));
+    }
+
+    #[test]
+    fn test_equality_with_stored_answers() {
+        assert_eq!(Answer::from(5129usize), "5129");
+        assert_eq!(Answer::from(vec![6, 9]), "6,9");
+        assert_eq!("6,9".parse::<Answer>().unwrap(), Answer::from((6, 9)));
+        assert_eq!("HE".parse::<Answer>().unwrap(), Answer::from("HE"));
+        assert_eq!("".parse::<Answer>().unwrap(), Answer::from(None::<i32>));
+        assert_eq!(Answer::from(142), 142usize);
+        assert_ne!(Answer::from(142), 143usize);
+        assert_eq!(Answer::Text(String::from("142")), 142usize);
+        assert_eq!(Answer::Multiple(vec![Answer::from(7)]), 7u8);
+        assert_ne!(Answer::Unsolved, 0usize);
+        assert_eq!(Answer::from(u128::MAX), u128::MAX);
+        assert_ne!(Answer::from(u128::MAX - 1), u128::MAX);
+        assert_ne!(Answer::from(5129usize), "512");
+        assert_ne!(Answer::from(5129usize), "51290");
+    }
+
+    #[test]
+    fn test_equality_by_variant() {
+        assert_ne!(Answer::Text(String::from("-")), Answer::Unsolved);
+        assert_ne!(Answer::Multiple(vec![Answer::from(1)]), Answer::from(1));
+        assert_ne!(Answer::Text(String::from("142")), Answer::from(142));
+        assert_eq!(Answer::Text(String::from("-")), "-");
+        assert_eq!(Answer::Unsolved, "-");
+    }
+}
@@ -0,0 +1,153 @@
+use std::{
+    cell::Cell,
+    env, fmt,
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+// Time budgets for solvers. A solver can't be stopped from the outside, so
+// long running loops call `checkpoint()` and the part is unwound from there
+// once its budget is spent. Without a budget a checkpoint does nothing.
+//
+// The deadline is kept per thread. Code that fans out over rayon takes the
+// deadline with `Deadline::current()` and passes it down to the closures, they
+// must not call `checkpoint()`: a worker waiting on a nested job may steal one
+// of another part and would check that part's deadline instead.
+
+const DEFAULT_BUDGET: Duration = Duration::from_secs(300);
+
+// Unwind payload of a part that ran out of time
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimedOut {
+    pub budget: Duration,
+}
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "timed out after {:?}", self.budget)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Deadline {
+    expires: Option<(Instant, Duration)>,
+}
+
+thread_local! {
+    static CURRENT: Cell<Deadline> = Cell::new(Deadline::default());
+}
+
+impl Deadline {
+    pub fn after(budget: Duration) -> Self {
+        Self {
+            expires: Some((Instant::now() + budget, budget)),
+        }
+    }
+
+    pub fn current() -> Self {
+        CURRENT.with(|current| current.get())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires
+            .is_some_and(|(instant, _)| Instant::now() >= instant)
+    }
+
+    pub fn checkpoint(&self) {
+        if let Some((instant, budget)) = self.expires {
+            if Instant::now() >= instant {
+                // resume_unwind skips the panic hook, a timeout is not a bug
+                panic::resume_unwind(Box::new(TimedOut { budget }));
+            }
+        }
+    }
+}
+
+pub fn checkpoint() {
+    Deadline::current().checkpoint();
+}
+
+// A budget in seconds, 0 turns the budget off
+pub fn parse_budget(seconds: &str) -> Result<Option<Duration>, String> {
+    match seconds.trim().parse::<f64>() {
+        Ok(seconds) if seconds <= 0.0 => Ok(None),
+        Ok(seconds) if seconds.is_finite() => Ok(Some(Duration::from_secs_f64(seconds))),
+        _ => Err(format!("Invalid timeout {}", seconds)),
+    }
+}
+
+// The budget from AOC_TIMEOUT, 300 seconds when it isn't set
+pub fn configured_budget() -> Option<Duration> {
+    env::var("AOC_TIMEOUT")
+        .ok()
+        .and_then(|seconds| parse_budget(&seconds).ok())
+        .unwrap_or(Some(DEFAULT_BUDGET))
+}
+
+// Runs `solve` with the checkpoints of this thread bound to `budget`. Other
+// panics are passed on as they are.
+pub fn with_time_budget<R>(
+    budget: Option<Duration>,
+    solve: impl FnOnce() -> R,
+) -> Result<R, TimedOut> {
+    let deadline = budget.map(Deadline::after).unwrap_or_default();
+    let previous = CURRENT.with(|current| current.replace(deadline));
+    let result = panic::catch_unwind(AssertUnwindSafe(solve));
+    CURRENT.with(|current| current.set(previous));
+    match result {
+        Ok(result) => Ok(result),
+        Err(payload) => match payload.downcast::<TimedOut>() {
+            Ok(timed_out) => Err(*timed_out),
+            Err(payload) => panic::resume_unwind(payload),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spin() -> usize {
+        let mut iterations = 0;
+        loop {
+            checkpoint();
+            iterations += 1;
+        }
+    }
+
+    #[test]
+    fn test_budget_stops_solver() {
+        let budget = Duration::from_millis(20);
+        assert_eq!(
+            Err(TimedOut { budget }),
+            with_time_budget(Some(budget), spin)
+        );
+        // the deadline doesn't outlive the part
+        assert!(!Deadline::current().is_expired());
+        assert_eq!(Ok(3), with_time_budget(None, || 3));
+    }
+
+    #[test]
+    fn test_parse_budget() {
+        assert_eq!(Ok(Some(Duration::from_millis(1500))), parse_budget("1.5"));
+        assert_eq!(Ok(None), parse_budget("0"));
+        assert!(parse_budget("soon").is_err());
+    }
+
+    #[test]
+    fn test_deadline_crosses_threads() {
+        let budget = Duration::from_millis(20);
+        let result = with_time_budget(Some(budget), || {
+            let deadline = Deadline::current();
+            std::thread::scope(|scope| {
+                scope
+                    .spawn(move || loop {
+                        deadline.checkpoint();
+                    })
+                    .join()
+            })
+        });
+        let payload = result.unwrap().unwrap_err();
+        assert_eq!(Some(&TimedOut { budget }), payload.downcast_ref());
+    }
+}
@@ -0,0 +1,212 @@
+use std::fmt;
+
+use super::random::Rng;
+
+// The first input two implementations disagree on, shrunk as far as possible
+// while they still disagree
+#[derive(Debug, PartialEq)]
+pub struct Disagreement<I, O> {
+    pub left_name: String,
+    pub right_name: String,
+    pub case: usize,
+    pub original: I,
+    pub input: I,
+    pub left: O,
+    pub right: O,
+}
+
+impl<I: fmt::Debug, O: fmt::Debug> fmt::Display for Disagreement<I, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} and {} disagree on case {}",
+            self.left_name, self.right_name, self.case
+        )?;
+        writeln!(f, "original input: {:?}", self.original)?;
+        writeln!(f, "minimised input: {:?}", self.input)?;
+        writeln!(f, "{}: {:?}", self.left_name, self.left)?;
+        write!(f, "{}: {:?}", self.right_name, self.right)
+    }
+}
+
+type Implementation<'a, I, O> = Box<dyn Fn(&I) -> O + 'a>;
+type Shrinker<'a, I> = Box<dyn Fn(&I) -> Vec<I> + 'a>;
+
+// Runs two implementations of the same thing on the same inputs. Without a
+// shrinker the failing input is reported as is, with one every candidate it
+// returns is tried and the first that still disagrees replaces the input until
+// no candidate does.
+pub struct Differential<'a, I, O> {
+    left: (String, Implementation<'a, I, O>),
+    right: (String, Implementation<'a, I, O>),
+    shrinker: Option<Shrinker<'a, I>>,
+}
+
+impl<'a, I: Clone, O: PartialEq> Differential<'a, I, O> {
+    pub fn new(
+        left_name: &str,
+        left: impl Fn(&I) -> O + 'a,
+        right_name: &str,
+        right: impl Fn(&I) -> O + 'a,
+    ) -> Self {
+        Self {
+            left: (left_name.to_owned(), Box::new(left)),
+            right: (right_name.to_owned(), Box::new(right)),
+            shrinker: None,
+        }
+    }
+
+    pub fn with_shrinker(mut self, shrinker: impl Fn(&I) -> Vec<I> + 'a) -> Self {
+        self.shrinker = Some(Box::new(shrinker));
+        self
+    }
+
+    fn disagrees(&self, input: &I) -> bool {
+        (self.left.1)(input) != (self.right.1)(input)
+    }
+
+    pub fn minimise(&self, input: &I) -> I {
+        let mut input = input.clone();
+        if let Some(shrinker) = &self.shrinker {
+            while let Some(smaller) = shrinker(&input).into_iter().find(|c| self.disagrees(c)) {
+                input = smaller;
+            }
+        }
+        input
+    }
+
+    // Returns how many inputs were checked when all of them agree
+    pub fn check(&self, inputs: impl IntoIterator<Item = I>) -> Result<usize, Disagreement<I, O>> {
+        let mut checked = 0;
+        for (case, input) in inputs.into_iter().enumerate() {
+            if self.disagrees(&input) {
+                let minimised = self.minimise(&input);
+                return Err(Disagreement {
+                    left_name: self.left.0.clone(),
+                    right_name: self.right.0.clone(),
+                    case,
+                    left: (self.left.1)(&minimised),
+                    right: (self.right.1)(&minimised),
+                    original: input,
+                    input: minimised,
+                });
+            }
+            checked += 1;
+        }
+        Ok(checked)
+    }
+
+    pub fn check_random(
+        &self,
+        seed: u64,
+        cases: usize,
+        mut generate: impl FnMut(&mut Rng) -> I,
+    ) -> Result<usize, Disagreement<I, O>> {
+        let mut rng = Rng::new(seed);
+        self.check((0..cases).map(|_| generate(&mut rng)))
+    }
+}
+
+// Candidates with one element removed, never shrinks below `min_len` elements
+pub fn shrink_vec<T: Clone>(items: &[T], min_len: usize) -> Vec<Vec<T>> {
+    if items.len() <= min_len {
+        return Vec::new();
+    }
+    (0..items.len())
+        .map(|index| {
+            let mut smaller = items.to_vec();
+            smaller.remove(index);
+            smaller
+        })
+        .collect()
+}
+
+// Smaller numbers to try in place of `value`, closest to `min` first
+pub fn shrink_number(value: u128, min: u128) -> Vec<u128> {
+    let mut candidates = Vec::new();
+    let mut distance = value.saturating_sub(min);
+    while distance > 0 {
+        candidates.push(value - distance);
+        distance /= 2;
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum(numbers: &[u128]) -> u128 {
+        numbers.iter().sum()
+    }
+
+    // Wrong as soon as a number above 10 is part of the input
+    fn broken_sum(numbers: &[u128]) -> u128 {
+        numbers.iter().filter(|n| **n <= 10).sum()
+    }
+
+    fn shrink(numbers: &[u128]) -> Vec<Vec<u128>> {
+        let mut candidates = shrink_vec(numbers, 0);
+        for (index, number) in numbers.iter().enumerate() {
+            for smaller in shrink_number(*number, 0) {
+                let mut candidate = numbers.to_vec();
+                candidate[index] = smaller;
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+
+    #[test]
+    fn test_agreement() {
+        let differential = Differential::new(
+            "sum",
+            |n: &Vec<u128>| sum(n),
+            "fold",
+            |numbers| numbers.iter().rev().sum(),
+        );
+        assert_eq!(Ok(2), differential.check(vec![vec![1, 2], vec![]]));
+        let result = differential.check_random(1, 50, |rng| {
+            (0..rng.range(0..5))
+                .map(|_| rng.range(0..100) as u128)
+                .collect()
+        });
+        assert_eq!(Ok(50), result);
+    }
+
+    #[test]
+    fn test_disagreement_is_minimised() {
+        let differential = Differential::new(
+            "sum",
+            |n: &Vec<u128>| sum(n),
+            "broken_sum",
+            |n| broken_sum(n),
+        );
+        let inputs = vec![vec![1, 2, 3], vec![4, 5, 60, 7]];
+        let disagreement = differential.check(inputs.clone()).unwrap_err();
+        assert_eq!(1, disagreement.case);
+        assert_eq!(vec![4, 5, 60, 7], disagreement.input);
+
+        let disagreement = differential
+            .with_shrinker(|n| shrink(n))
+            .check(inputs)
+            .unwrap_err();
+        assert_eq!(vec![11], disagreement.input);
+        assert_eq!(vec![4, 5, 60, 7], disagreement.original);
+        assert_eq!((11, 0), (disagreement.left, disagreement.right));
+        assert!(disagreement
+            .to_string()
+            .starts_with("sum and broken_sum disagree on case 1"));
+    }
+
+    #[test]
+    fn test_shrink_helpers() {
+        assert_eq!(
+            vec![vec![2, 3], vec![1, 3], vec![1, 2]],
+            shrink_vec(&[1, 2, 3], 1)
+        );
+        assert!(shrink_vec(&[1], 1).is_empty());
+        assert_eq!(vec![0, 50, 75, 88, 94, 97, 99], shrink_number(100, 0));
+        assert!(shrink_number(3, 3).is_empty());
+    }
+}
@@ -1,20 +1,194 @@
-#[cfg(test)]
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
-#[cfg(test)]
-pub fn get_input(file_path: &str, file_name: &str) -> String {
-    let mut assets = Path::new(file_path)
+// `file!()` is relative to the crate root, resolve it from there so the runner
+// finds the files of a day from any working directory
+pub fn get_day_directory(file_path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join(file_path)
         .parent()
         .unwrap()
-        .to_str()
-        .unwrap()
-        .to_owned();
-    assets.push_str("/assets/");
-    assets.push_str(file_name);
-    fs::read_to_string(assets).unwrap()
+        .to_path_buf()
+}
+
+fn get_asset_path(file_path: &str, file_name: &str) -> PathBuf {
+    get_day_directory(file_path).join("assets").join(file_name)
+}
+
+// The file exactly as it is on disk
+pub fn get_raw_input(file_path: &str, file_name: &str) -> String {
+    fs::read_to_string(get_asset_path(file_path, file_name)).unwrap()
+}
+
+// The file with CRLF / CR line endings turned into LF, set AOC_LINT_INPUT=1 to
+// print warnings about inputs that look truncated or contain stray whitespace
+pub fn get_input(file_path: &str, file_name: &str) -> String {
+    let raw = get_raw_input(file_path, file_name);
+    if std::env::var("AOC_LINT_INPUT").is_ok_and(|value| value != "0") {
+        for lint in lint_input(&raw) {
+            eprintln!("warning: {}/{}: {}", file_path, file_name, lint);
+        }
+    }
+    normalize_line_endings(&raw)
+}
+
+// Same as get_input but without the trailing newline(s), for days that split
+// the input on '\n' themselves
+pub fn get_trimmed_input(file_path: &str, file_name: &str) -> String {
+    trim_trailing_newlines(&get_input(file_path, file_name)).to_owned()
 }
 
-#[cfg(test)]
 pub fn get_rows(input: String) -> Vec<String> {
-    input.split('\n').map(|f| f.to_owned()).collect()
+    let input = normalize_line_endings(&input);
+    let mut rows: Vec<String> = input.split('\n').map(|f| f.to_owned()).collect();
+    // a trailing newline should not result in an empty last row
+    if input.ends_with('\n') {
+        rows.pop();
+    }
+    rows
+}
+
+pub fn normalize_line_endings(input: &str) -> String {
+    input.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+pub fn trim_trailing_newlines(input: &str) -> &str {
+    input.trim_end_matches(['\n', '\r'])
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InputLint {
+    Empty,
+    CarriageReturns,
+    LeadingBlankLines,
+    TrailingBlankLines(usize),
+    TrailingWhitespace { line: usize },
+    Tabs { line: usize },
+    ShortLastLine { expected: usize, actual: usize },
+    DanglingSeparator(char),
+}
+
+impl std::fmt::Display for InputLint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputLint::Empty => write!(f, "input is empty"),
+            InputLint::CarriageReturns => write!(f, "input contains carriage returns"),
+            InputLint::LeadingBlankLines => write!(f, "input starts with a blank line"),
+            InputLint::TrailingBlankLines(count) => {
+                write!(f, "input ends with {} blank line(s)", count)
+            }
+            InputLint::TrailingWhitespace { line } => {
+                write!(f, "line {} has trailing whitespace", line)
+            }
+            InputLint::Tabs { line } => write!(f, "line {} contains a tab", line),
+            InputLint::ShortLastLine { expected, actual } => write!(
+                f,
+                "last line is {} characters long while all others are {}, input looks truncated",
+                actual, expected
+            ),
+            InputLint::DanglingSeparator(c) => {
+                write!(f, "input ends with '{}', input looks truncated", c)
+            }
+        }
+    }
+}
+
+pub fn lint_input(raw: &str) -> Vec<InputLint> {
+    let mut lints = Vec::new();
+    if raw.trim().is_empty() {
+        lints.push(InputLint::Empty);
+        return lints;
+    }
+    if raw.contains('\r') {
+        lints.push(InputLint::CarriageReturns);
+    }
+    let input = normalize_line_endings(raw);
+    if input.starts_with('\n') {
+        lints.push(InputLint::LeadingBlankLines);
+    }
+    let trailing_newlines = input.len() - input.trim_end_matches('\n').len();
+    if trailing_newlines > 1 {
+        lints.push(InputLint::TrailingBlankLines(trailing_newlines - 1));
+    }
+
+    let lines: Vec<&str> = trim_trailing_newlines(&input).split('\n').collect();
+    for (index, line) in lines.iter().enumerate() {
+        if line.ends_with([' ', '\t']) {
+            lints.push(InputLint::TrailingWhitespace { line: index + 1 });
+        }
+        if line.contains('\t') {
+            lints.push(InputLint::Tabs { line: index + 1 });
+        }
+    }
+
+    // grids have lines of equal length, a shorter last line means a bad copy
+    if let Some((last, others)) = lines.split_last() {
+        if others.len() > 1 {
+            let expected = others[0].chars().count();
+            let actual = last.chars().count();
+            if others.iter().all(|line| line.chars().count() == expected) && actual < expected {
+                lints.push(InputLint::ShortLastLine { expected, actual });
+            }
+        }
+        // '-' and '=' are not in the list, they can end a day 15 step like `xx-`
+        if let Some(c) = last.trim_end().chars().last() {
+            if [',', ':', '|'].contains(&c) {
+                lints.push(InputLint::DanglingSeparator(c));
+            }
+        }
+    }
+    lints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_rows_normalizes_line_endings() {
+        assert_eq!(vec!["ab", "cd"], get_rows(String::from("ab\r\ncd\r\n")));
+        assert_eq!(vec!["ab", "cd"], get_rows(String::from("ab\ncd")));
+        assert_eq!(vec!["ab", "", "cd"], get_rows(String::from("ab\n\ncd\n")));
+    }
+
+    #[test]
+    fn trimmed_view() {
+        assert_eq!("ab\ncd", trim_trailing_newlines("ab\ncd\n\n"));
+        assert_eq!("ab\ncd", trim_trailing_newlines("ab\ncd\r\n"));
+        assert_eq!("ab\r\ncd", trim_trailing_newlines("ab\r\ncd"));
+    }
+
+    #[test]
+    fn lint_clean_input() {
+        assert_eq!(Vec::<InputLint>::new(), lint_input("#..\n.#.\n..#\n"));
+        assert_eq!(Vec::<InputLint>::new(), lint_input("1,2\n3,4"));
+        assert_eq!(Vec::<InputLint>::new(), lint_input("rn=1,cm-"));
+        assert_eq!(Vec::<InputLint>::new(), lint_input("a = 1\nb ="));
+    }
+
+    #[test]
+    fn lint_suspicious_input() {
+        assert_eq!(vec![InputLint::Empty], lint_input("\n"));
+        assert_eq!(
+            vec![InputLint::CarriageReturns, InputLint::TrailingBlankLines(1)],
+            lint_input("ab\r\ncd\r\n\r\n")
+        );
+        assert_eq!(
+            vec![InputLint::TrailingWhitespace { line: 2 }],
+            lint_input("abc\nabc \nabc")
+        );
+        assert_eq!(
+            vec![InputLint::ShortLastLine {
+                expected: 3,
+                actual: 1
+            }],
+            lint_input("#..\n.#.\n.")
+        );
+        assert_eq!(
+            vec![InputLint::DanglingSeparator(',')],
+            lint_input("rn=1,cm-,")
+        );
+    }
 }
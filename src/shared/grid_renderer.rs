@@ -0,0 +1,192 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+pub type Cell = (usize, usize);
+
+const RESET: &str = "\x1b[0m";
+const PATH_COLOUR: &str = "\x1b[1;33m";
+const VISITED_COLOUR: &str = "\x1b[46;30m";
+const ACTOR_COLOUR: &str = "\x1b[1;31m";
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Arrow {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Arrow {
+    pub fn between(from: Cell, to: Cell) -> Option<Self> {
+        match (from, to) {
+            ((x1, _), (x2, _)) if x1 > x2 => Some(Arrow::Left),
+            ((x1, _), (x2, _)) if x2 > x1 => Some(Arrow::Right),
+            ((_, y1), (_, y2)) if y2 > y1 => Some(Arrow::Down),
+            ((_, y1), (_, y2)) if y2 < y1 => Some(Arrow::Up),
+            _ => None,
+        }
+    }
+
+    pub fn as_char(&self) -> char {
+        match self {
+            Arrow::Up => '^',
+            Arrow::Right => '>',
+            Arrow::Down => 'v',
+            Arrow::Left => '<',
+        }
+    }
+}
+
+// Renders any grid through its Display implementation, with overlays drawn on
+// top of the base characters. From lowest to highest priority: visited cells,
+// path, direction arrows and the actor. Plain mode (the default) only changes
+// characters so the output can be compared in tests, colour mode (with_colour
+// or the alternate `{:#}` format) adds ANSI escape codes around the overlays.
+pub struct GridRenderer<F: Fn(usize, usize) -> char> {
+    width: usize,
+    height: usize,
+    base: F,
+    visited: HashSet<Cell>,
+    visited_marker: Option<char>,
+    path: HashSet<Cell>,
+    arrows: HashMap<Cell, Arrow>,
+    actor: Option<(Cell, Option<Arrow>)>,
+    colour: bool,
+}
+
+impl<F: Fn(usize, usize) -> char> GridRenderer<F> {
+    pub fn new(width: usize, height: usize, base: F) -> Self {
+        Self {
+            width,
+            height,
+            base,
+            visited: HashSet::new(),
+            visited_marker: None,
+            path: HashSet::new(),
+            arrows: HashMap::new(),
+            actor: None,
+            colour: false,
+        }
+    }
+
+    // Visited cells keep their base character unless a marker is given
+    pub fn with_visited(
+        mut self,
+        visited: impl IntoIterator<Item = Cell>,
+        marker: Option<char>,
+    ) -> Self {
+        self.visited.extend(visited);
+        self.visited_marker = marker;
+        self
+    }
+
+    // Every step of the path gets an arrow pointing to the next step, the first
+    // cell keeps its base character
+    pub fn with_path(mut self, path: &[Cell]) -> Self {
+        self.path.extend(path.iter().copied());
+        for step in path.windows(2) {
+            if let Some(arrow) = Arrow::between(step[0], step[1]) {
+                self.arrows.insert(step[1], arrow);
+            }
+        }
+        self
+    }
+
+    pub fn with_arrows(mut self, arrows: impl IntoIterator<Item = (Cell, Arrow)>) -> Self {
+        self.arrows.extend(arrows);
+        self
+    }
+
+    pub fn with_actor(mut self, cell: Cell, direction: Option<Arrow>) -> Self {
+        self.actor = Some((cell, direction));
+        self
+    }
+
+    pub fn with_colour(mut self, colour: bool) -> Self {
+        self.colour = colour;
+        self
+    }
+
+    fn cell(&self, cell: Cell) -> (char, Option<&'static str>) {
+        if let Some((actor, direction)) = self.actor {
+            if actor == cell {
+                let c = direction.map(|arrow| arrow.as_char()).unwrap_or('@');
+                return (c, Some(ACTOR_COLOUR));
+            }
+        }
+        if let Some(arrow) = self.arrows.get(&cell) {
+            return (arrow.as_char(), Some(PATH_COLOUR));
+        }
+        let base = (self.base)(cell.0, cell.1);
+        if self.path.contains(&cell) {
+            return (base, Some(PATH_COLOUR));
+        }
+        if self.visited.contains(&cell) {
+            return (self.visited_marker.unwrap_or(base), Some(VISITED_COLOUR));
+        }
+        (base, None)
+    }
+}
+
+impl<F: Fn(usize, usize) -> char> fmt::Display for GridRenderer<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let colour = self.colour || f.alternate();
+        for y in 0..self.height {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for x in 0..self.width {
+                match self.cell((x, y)) {
+                    (c, Some(code)) if colour => write!(f, "{}{}{}", code, c, RESET)?,
+                    (c, _) => write!(f, "{}", c)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Vec<Vec<char>> {
+        vec!["....".chars().collect(), ".#..".chars().collect()]
+    }
+
+    #[test]
+    fn test_plain() {
+        let grid = grid();
+        let renderer = GridRenderer::new(4, 2, |x, y| grid[y][x]);
+        assert_eq!("....\n.#..", renderer.to_string());
+    }
+
+    #[test]
+    fn test_overlays() {
+        let grid = grid();
+        let renderer = GridRenderer::new(4, 2, |x, y| grid[y][x])
+            .with_visited([(1, 1), (3, 1)], Some('X'))
+            .with_path(&[(0, 0), (1, 0), (2, 0), (2, 1)])
+            .with_actor((3, 0), Some(Arrow::Down));
+        assert_eq!(".>>v\n.XvX", renderer.to_string());
+    }
+
+    #[test]
+    fn test_colour() {
+        let grid = grid();
+        let renderer = GridRenderer::new(4, 2, |x, y| grid[y][x])
+            .with_actor((0, 0), None)
+            .with_colour(true);
+        assert_eq!(
+            format!("{}@{}...\n.#..", ACTOR_COLOUR, RESET),
+            renderer.to_string()
+        );
+        let renderer = GridRenderer::new(4, 2, |x, y| grid[y][x]).with_actor((0, 0), None);
+        assert_eq!(
+            format!("{}@{}...\n.#..", ACTOR_COLOUR, RESET),
+            format!("{:#}", renderer)
+        );
+    }
+}
@@ -0,0 +1,384 @@
+use std::{fmt::Write as _, fs, io, path::Path, str::FromStr};
+
+use super::grid_renderer::Cell;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const GREY: Rgb = Rgb(128, 128, 128);
+    pub const RED: Rgb = Rgb(220, 40, 40);
+    pub const BLUE: Rgb = Rgb(40, 80, 200);
+    pub const YELLOW: Rgb = Rgb(250, 210, 60);
+
+    // 0 is white, max is black
+    pub fn shade(value: usize, max: usize) -> Rgb {
+        let level = 255 - (value.min(max) * 255 / max.max(1)) as u8;
+        Rgb(level, level, level)
+    }
+
+    fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+// Exports a grid as an image, every cell becomes a square of `scale` pixels and
+// paths are drawn as lines through the centres of their cells. Supports PPM
+// and PNG (both written without any dependency) and SVG.
+const DEFAULT_SCALE: usize = 5;
+
+pub struct GridImage<F: Fn(usize, usize) -> Rgb> {
+    width: usize,
+    height: usize,
+    cells: F,
+    paths: Vec<(Vec<Cell>, Rgb)>,
+    scale: usize,
+}
+
+impl<F: Fn(usize, usize) -> Rgb> GridImage<F> {
+    pub fn new(width: usize, height: usize, cells: F) -> Self {
+        Self {
+            width,
+            height,
+            cells,
+            paths: Vec::new(),
+            scale: DEFAULT_SCALE,
+        }
+    }
+
+    pub fn with_path(mut self, path: &[Cell], colour: Rgb) -> Self {
+        self.paths.push((path.to_vec(), colour));
+        self
+    }
+
+    pub fn with_scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    fn pixel_size(&self) -> (usize, usize) {
+        (self.width * self.scale, self.height * self.scale)
+    }
+
+    fn raster(&self) -> Vec<Rgb> {
+        let (width, height) = self.pixel_size();
+        let mut pixels = vec![Rgb::WHITE; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                pixels[y * width + x] = (self.cells)(x / self.scale, y / self.scale);
+            }
+        }
+        let thickness = (self.scale / 3).max(1);
+        let start = (self.scale - thickness) / 2;
+        for (path, colour) in &self.paths {
+            for step in path.windows(2) {
+                let (from, to) = (step[0], step[1]);
+                let (min_x, max_x) = (from.0.min(to.0), from.0.max(to.0));
+                let (min_y, max_y) = (from.1.min(to.1), from.1.max(to.1));
+                let pixel_x = min_x * self.scale + start..max_x * self.scale + start + thickness;
+                for x in pixel_x {
+                    let pixel_y =
+                        min_y * self.scale + start..max_y * self.scale + start + thickness;
+                    for y in pixel_y {
+                        pixels[y * width + x] = *colour;
+                    }
+                }
+            }
+        }
+        pixels
+    }
+
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let (width, height) = self.pixel_size();
+        let mut ppm = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        for pixel in self.raster() {
+            ppm.extend([pixel.0, pixel.1, pixel.2]);
+        }
+        ppm
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        let (width, height) = self.pixel_size();
+        let pixels = self.raster();
+        // every scanline starts with filter type 0 (none)
+        let mut raw = Vec::with_capacity(height * (width * 3 + 1));
+        for row in pixels.chunks(width) {
+            raw.push(0);
+            for pixel in row {
+                raw.extend([pixel.0, pixel.1, pixel.2]);
+            }
+        }
+
+        let mut ihdr = Vec::new();
+        ihdr.extend((width as u32).to_be_bytes());
+        ihdr.extend((height as u32).to_be_bytes());
+        // bit depth 8, colour type RGB, default compression, filter and interlace
+        ihdr.extend([8, 2, 0, 0, 0]);
+
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        png_chunk(&mut png, b"IHDR", &ihdr);
+        png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    pub fn to_svg(&self) -> String {
+        let (width, height) = self.pixel_size();
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" shape-rendering=\"crispEdges\">\n",
+            width, height
+        );
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let colour = (self.cells)(x, y);
+                if colour == Rgb::WHITE {
+                    continue;
+                }
+                writeln!(
+                    svg,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    x * self.scale,
+                    y * self.scale,
+                    self.scale,
+                    self.scale,
+                    colour.hex()
+                )
+                .unwrap();
+            }
+        }
+        let centre = |c: usize| c * self.scale + self.scale / 2;
+        for (path, colour) in &self.paths {
+            let points: Vec<String> = path
+                .iter()
+                .map(|cell| format!("{},{}", centre(cell.0), centre(cell.1)))
+                .collect();
+            writeln!(
+                svg,
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                points.join(" "),
+                colour.hex(),
+                (self.scale / 3).max(1)
+            )
+            .unwrap();
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    pub fn encode(&self, format: ImageFormat) -> Vec<u8> {
+        match format {
+            ImageFormat::Ppm => self.to_ppm(),
+            ImageFormat::Png => self.to_png(),
+            ImageFormat::Svg => self.to_svg().into_bytes(),
+        }
+    }
+
+    pub fn save(self, path: &Path, config: &ExportConfig) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.with_scale(config.scale).encode(config.format))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    // The format matching the extension of the path: .ppm, .png or .svg
+    pub fn from_path(path: &Path) -> Option<ImageFormat> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ppm" => Ok(ImageFormat::Ppm),
+            "png" => Ok(ImageFormat::Png),
+            "svg" => Ok(ImageFormat::Svg),
+            _ => Err(format!("Unsupported image format {}", s)),
+        }
+    }
+}
+
+pub struct ExportConfig {
+    pub format: ImageFormat,
+    // Pixels per cell
+    pub scale: usize,
+}
+
+impl ExportConfig {
+    pub fn new(format: ImageFormat) -> Self {
+        Self {
+            format,
+            scale: DEFAULT_SCALE,
+        }
+    }
+}
+
+type SaveImage = dyn Fn(&Path, &ExportConfig) -> io::Result<()>;
+
+// A day that can be drawn with the export command, it solves the real input
+// and saves the picture of the solution
+pub struct DayImage {
+    pub year: u16,
+    pub day: u8,
+    save: Box<SaveImage>,
+}
+
+impl DayImage {
+    pub fn new(
+        year: u16,
+        day: u8,
+        save: impl Fn(&Path, &ExportConfig) -> io::Result<()> + 'static,
+    ) -> Self {
+        Self {
+            year,
+            day,
+            save: Box::new(save),
+        }
+    }
+
+    pub fn save(&self, path: &Path, config: &ExportConfig) -> io::Result<()> {
+        (self.save)(path, config)
+    }
+}
+
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(kind.iter().chain(data.iter()).copied());
+    png.extend(crc.to_be_bytes());
+}
+
+fn crc32(bytes: impl Iterator<Item = u8>) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in bytes {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+// zlib stream made of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut zlib = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = data.chunks(u16::MAX as usize).collect();
+    if blocks.is_empty() {
+        zlib.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    for (index, block) in blocks.iter().enumerate() {
+        zlib.push((index == blocks.len() - 1) as u8);
+        let len = block.len() as u16;
+        zlib.extend(len.to_le_bytes());
+        zlib.extend((!len).to_le_bytes());
+        zlib.extend(*block);
+    }
+    zlib.extend(adler32(data).to_be_bytes());
+    zlib
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image() -> GridImage<impl Fn(usize, usize) -> Rgb> {
+        GridImage::new(2, 2, |x, y| if x == y { Rgb::BLACK } else { Rgb::WHITE }).with_scale(1)
+    }
+
+    #[test]
+    fn test_ppm() {
+        let mut expected = b"P6\n2 2\n255\n".to_vec();
+        expected.extend([0, 0, 0, 255, 255, 255, 255, 255, 255, 0, 0, 0]);
+        assert_eq!(expected, image().to_ppm());
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(
+            Some(ImageFormat::Png),
+            ImageFormat::from_path(Path::new("target/loop.png"))
+        );
+        assert_eq!(None, ImageFormat::from_path(Path::new("target/loop.jpg")));
+        assert_eq!(None, ImageFormat::from_path(Path::new("target/loop")));
+        assert_eq!(Ok(ImageFormat::Svg), "svg".parse());
+        assert_eq!(
+            image().to_svg().into_bytes(),
+            image().encode(ImageFormat::Svg)
+        );
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(0xcbf4_3926, crc32(b"123456789".iter().copied()));
+        assert_eq!(0x11e6_0398, adler32(b"Wikipedia"));
+    }
+
+    #[test]
+    fn test_png() {
+        let png = image().to_png();
+        assert_eq!(&[0x89, b'P', b'N', b'G'], &png[..4]);
+        // walk the chunks and check their checksums
+        let mut offset = 8;
+        let mut kinds = Vec::new();
+        while offset < png.len() {
+            let len = u32::from_be_bytes(png[offset..offset + 4].try_into().unwrap()) as usize;
+            let body = &png[offset + 4..offset + 8 + len];
+            let crc =
+                u32::from_be_bytes(png[offset + 8 + len..offset + 12 + len].try_into().unwrap());
+            assert_eq!(crc32(body.iter().copied()), crc);
+            kinds.push(String::from_utf8(body[..4].to_vec()).unwrap());
+            if &body[..4] == b"IDAT" {
+                // a single stored block: header, block header, data and adler32
+                let data = &body[4..];
+                assert_eq!(&[0x78, 0x01, 1, 14, 0], &data[..5]);
+                assert_eq!(
+                    vec![0, 0, 0, 0, 255, 255, 255, 0, 255, 255, 255, 0, 0, 0],
+                    data[7..21].to_vec()
+                );
+            }
+            offset += len + 12;
+        }
+        assert_eq!(vec!["IHDR", "IDAT", "IEND"], kinds);
+    }
+
+    #[test]
+    fn test_path_and_svg() {
+        let image = GridImage::new(3, 1, |_, _| Rgb::WHITE)
+            .with_scale(3)
+            .with_path(&[(0, 0), (2, 0)], Rgb::RED);
+        let ppm = image.to_ppm();
+        let pixels = &ppm[b"P6\n9 3\n255\n".len()..];
+        // the middle row is red from the centre of the first to the last cell
+        let row: Vec<&[u8]> = pixels[9 * 3..18 * 3].chunks(3).collect();
+        assert_eq!(&[255, 255, 255], row[0]);
+        assert!(row[1..8].iter().all(|p| *p == [220, 40, 40]));
+        assert_eq!(&[255, 255, 255], row[8]);
+
+        let svg = image.to_svg();
+        assert!(svg.contains("<polyline points=\"1,1 7,1\" fill=\"none\" stroke=\"#dc2828\""));
+        assert!(!svg.contains("<rect"));
+    }
+}
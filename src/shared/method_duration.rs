@@ -1,9 +1,18 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::{
+    panic::Location,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
+use super::trace::{self, Level};
+
+#[track_caller]
 pub fn log_method_duration<R>(function: impl FnOnce() -> R) -> R {
+    let caller = Location::caller().file();
     let start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
     let result: R = function();
     let end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
-    println!("{:?}", end - start);
+    if trace::enabled(Level::Info, caller) {
+        trace::log(Level::Info, caller, format_args!("{:?}", end - start));
+    }
     result
 }
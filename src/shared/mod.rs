@@ -1,4 +1,21 @@
+pub mod allocation;
+pub mod animation;
+pub mod answer;
+pub mod cancellation;
+#[cfg(test)]
+pub mod differential;
 pub mod file_parser;
+pub mod grid_renderer;
+pub mod image_export;
 pub mod linked_list;
 pub mod list_util;
 pub mod method_duration;
+pub mod ocr;
+pub mod puzzle_page;
+#[cfg(test)]
+pub mod random;
+#[cfg(test)]
+pub mod readme;
+pub mod registry;
+pub mod session;
+pub mod trace;
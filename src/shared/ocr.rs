@@ -0,0 +1,259 @@
+use std::fmt;
+
+// Reads capital letters drawn with `#` (or `█`) in the two fonts Advent of Code
+// uses for answers: 4x6 glyphs on 6 rows and 6x10 glyphs on 10 rows. Glyphs are
+// separated by empty columns, every other character counts as background.
+
+const SMALL_FONT: [(char, &str); 18] = [
+    ('A', ".##.|#..#|#..#|####|#..#|#..#"),
+    ('B', "###.|#..#|###.|#..#|#..#|###."),
+    ('C', ".##.|#..#|#...|#...|#..#|.##."),
+    ('E', "####|#...|###.|#...|#...|####"),
+    ('F', "####|#...|###.|#...|#...|#..."),
+    ('G', ".##.|#..#|#...|#.##|#..#|.###"),
+    ('H', "#..#|#..#|####|#..#|#..#|#..#"),
+    ('I', ".###|..#.|..#.|..#.|..#.|.###"),
+    ('J', "..##|...#|...#|...#|#..#|.##."),
+    ('K', "#..#|#.#.|##..|#.#.|#.#.|#..#"),
+    ('L', "#...|#...|#...|#...|#...|####"),
+    ('O', ".##.|#..#|#..#|#..#|#..#|.##."),
+    ('P', "###.|#..#|#..#|###.|#...|#..."),
+    ('R', "###.|#..#|#..#|###.|#.#.|#..#"),
+    ('S', ".###|#...|#...|.##.|...#|###."),
+    ('U', "#..#|#..#|#..#|#..#|#..#|.##."),
+    ('Y', "#...#|#...#|.#.#.|..#..|..#..|..#.."),
+    ('Z', "####|...#|..#.|.#..|#...|####"),
+];
+
+#[rustfmt::skip]
+const LARGE_FONT: [(char, &str); 15] = [
+    ('A', "..##..|.#..#.|#....#|#....#|#....#|######|#....#|#....#|#....#|#....#"),
+    ('B', "#####.|#....#|#....#|#....#|#####.|#....#|#....#|#....#|#....#|#####."),
+    ('C', ".####.|#....#|#.....|#.....|#.....|#.....|#.....|#.....|#....#|.####."),
+    ('E', "######|#.....|#.....|#.....|#####.|#.....|#.....|#.....|#.....|######"),
+    ('F', "######|#.....|#.....|#.....|#####.|#.....|#.....|#.....|#.....|#....."),
+    ('G', ".####.|#....#|#.....|#.....|#.....|#..###|#....#|#....#|#...##|.###.#"),
+    ('H', "#....#|#....#|#....#|#....#|######|#....#|#....#|#....#|#....#|#....#"),
+    ('J', "...###|....#.|....#.|....#.|....#.|....#.|....#.|#...#.|#...#.|.###.."),
+    ('K', "#....#|#...#.|#..#..|#.#...|##....|##....|#.#...|#..#..|#...#.|#....#"),
+    ('L', "#.....|#.....|#.....|#.....|#.....|#.....|#.....|#.....|#.....|######"),
+    ('N', "#....#|##...#|##...#|#.#..#|#.#..#|#..#.#|#..#.#|#...##|#...##|#....#"),
+    ('P', "#####.|#....#|#....#|#....#|#####.|#.....|#.....|#.....|#.....|#....."),
+    ('R', "#####.|#....#|#....#|#....#|#####.|#..#..|#...#.|#...#.|#....#|#....#"),
+    ('X', "#....#|#....#|.#..#.|.#..#.|..##..|..##..|.#..#.|.#..#.|#....#|#....#"),
+    ('Z', "######|.....#|.....#|....#.|...#..|..#...|.#....|#.....|#.....|######"),
+];
+
+#[derive(Debug, PartialEq)]
+pub enum OcrError {
+    Empty,
+    UnsupportedHeight(usize),
+    UnknownGlyph {
+        index: usize,
+        column: usize,
+        glyph: String,
+    },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OcrError::Empty => write!(f, "Nothing is drawn"),
+            OcrError::UnsupportedHeight(height) => write!(
+                f,
+                "Letters are {} rows high, only 6 and 10 are supported",
+                height
+            ),
+            OcrError::UnknownGlyph {
+                index,
+                column,
+                glyph,
+            } => write!(
+                f,
+                "Unknown glyph for letter {} starting at column {}:\n{}",
+                index + 1,
+                column,
+                glyph
+            ),
+        }
+    }
+}
+
+fn is_lit(c: char) -> bool {
+    c == '#' || c == '█'
+}
+
+pub fn recognise(text: &str) -> Result<String, OcrError> {
+    recognise_cells(text.lines().enumerate().flat_map(|(y, line)| {
+        line.chars()
+            .enumerate()
+            .filter(|(_, c)| is_lit(*c))
+            .map(move |(x, _)| (x, y))
+    }))
+}
+
+// For answers that are a set of lit (x, y) coordinates
+pub fn recognise_cells(
+    cells: impl IntoIterator<Item = (usize, usize)>,
+) -> Result<String, OcrError> {
+    let cells: Vec<(usize, usize)> = cells.into_iter().collect();
+    let width = cells.iter().map(|c| c.0 + 1).max().unwrap_or(0);
+    let height = cells.iter().map(|c| c.1 + 1).max().unwrap_or(0);
+    let mut grid = vec![vec![false; width]; height];
+    for (x, y) in cells {
+        grid[y][x] = true;
+    }
+    recognise_grid(&grid)
+}
+
+pub fn recognise_grid(grid: &[Vec<bool>]) -> Result<String, OcrError> {
+    let rows: Vec<&Vec<bool>> = grid.iter().skip_while(|row| !row.contains(&true)).collect();
+    let height = rows
+        .iter()
+        .rposition(|row| row.contains(&true))
+        .map(|last| last + 1)
+        .ok_or(OcrError::Empty)?;
+    let rows = &rows[..height];
+    let font: &[(char, &str)] = match height {
+        6 => &SMALL_FONT,
+        10 => &LARGE_FONT,
+        _ => return Err(OcrError::UnsupportedHeight(height)),
+    };
+
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let column_lit = |x: usize| rows.iter().any(|row| row.get(x) == Some(&true));
+
+    let mut answer = String::new();
+    let mut x = 0;
+    while x < width {
+        if !column_lit(x) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < width && column_lit(x) {
+            x += 1;
+        }
+        let glyph = rows
+            .iter()
+            .map(|row| {
+                (start..x)
+                    .map(|x| if row.get(x) == Some(&true) { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("|");
+        match font
+            .iter()
+            .find(|(_, pattern)| trim_columns(pattern) == glyph)
+        {
+            Some((letter, _)) => answer.push(*letter),
+            None => {
+                return Err(OcrError::UnknownGlyph {
+                    index: answer.len(),
+                    column: start,
+                    glyph: glyph.replace('|', "\n"),
+                })
+            }
+        }
+    }
+    Ok(answer)
+}
+
+// Glyphs are compared without the empty columns around them
+fn trim_columns(pattern: &str) -> String {
+    let rows: Vec<&str> = pattern.split('|').collect();
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let lit = |x: usize| rows.iter().any(|row| row.as_bytes().get(x) == Some(&b'#'));
+    let start = (0..width).find(|x| lit(*x)).unwrap_or(0);
+    let end = (0..width).rfind(|x| lit(*x)).map_or(0, |x| x + 1);
+    rows.iter()
+        .map(|row| &row[start..end])
+        .collect::<Vec<&str>>()
+        .join("|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Draws the letters next to each other with `gap` empty columns in between
+    fn draw(font: &[(char, &str)], letters: &str, gap: usize) -> String {
+        let glyphs: Vec<Vec<&str>> = letters
+            .chars()
+            .map(|letter| {
+                let (_, pattern) = font.iter().find(|(c, _)| *c == letter).unwrap();
+                pattern.split('|').collect()
+            })
+            .collect();
+        (0..glyphs[0].len())
+            .map(|y| {
+                glyphs
+                    .iter()
+                    .map(|glyph| glyph[y].to_owned())
+                    .collect::<Vec<String>>()
+                    .join(&".".repeat(gap))
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_small_font() {
+        let letters: String = SMALL_FONT.iter().map(|(c, _)| *c).collect();
+        assert_eq!(
+            Ok(letters.clone()),
+            recognise(&draw(&SMALL_FONT, &letters, 1))
+        );
+    }
+
+    #[test]
+    fn test_large_font() {
+        let letters: String = LARGE_FONT.iter().map(|(c, _)| *c).collect();
+        assert_eq!(
+            Ok(letters.clone()),
+            recognise(&draw(&LARGE_FONT, &letters, 2))
+        );
+    }
+
+    #[test]
+    fn test_padding_and_other_characters() {
+        let text = "\n          \n █  █ ████ \n █  █ █    \n ████ ███  \n █  █ █    \n █  █ █    \n █  █ ████ \n";
+        assert_eq!(Ok(String::from("HE")), recognise(text));
+    }
+
+    #[test]
+    fn test_cells() {
+        let cells = draw(&SMALL_FONT, "JZ", 1)
+            .lines()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .filter(|(_, c)| *c == '#')
+                    .map(move |(x, _)| (x + 3, y + 2))
+                    .collect::<Vec<(usize, usize)>>()
+            })
+            .collect::<Vec<(usize, usize)>>();
+        assert_eq!(Ok(String::from("JZ")), recognise_cells(cells));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Err(OcrError::Empty), recognise("....\n...."));
+        assert_eq!(Err(OcrError::UnsupportedHeight(2)), recognise("#\n#"));
+        let text = draw(&SMALL_FONT, "AB", 1).replacen("###.", "#.#.", 1);
+        let error = recognise(&text).unwrap_err();
+        assert_eq!(
+            OcrError::UnknownGlyph {
+                index: 1,
+                column: 5,
+                glyph: String::from("#.#.\n#..#\n###.\n#..#\n#..#\n###.")
+            },
+            error
+        );
+        assert!(error
+            .to_string()
+            .starts_with("Unknown glyph for letter 2 starting at column 5"));
+    }
+}
@@ -0,0 +1,198 @@
+use std::{fs, io, path::Path};
+
+// Converts a puzzle page into the README format of the repo: the text of the
+// `<article class="day-desc">` parts, the answers given so far and the line
+// about the stars earned. Inline markup like `<em>` and `<code>` is flattened
+// to plain text, `<pre>` blocks are kept as they are and list items each get
+// their own line.
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Open { name: String, attributes: &'a str },
+    Close(String),
+    Text(&'a str),
+}
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        if rest.starts_with('<') {
+            let end = match rest.find('>') {
+                Some(end) => end,
+                None => break,
+            };
+            let tag = rest[1..end].trim_end_matches('/');
+            rest = &rest[end + 1..];
+            if let Some(name) = tag.strip_prefix('/') {
+                tokens.push(Token::Close(name.trim().to_ascii_lowercase()));
+            } else if !tag.starts_with('!') {
+                let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+                tokens.push(Token::Open {
+                    name: name.to_ascii_lowercase(),
+                    attributes,
+                });
+            }
+            continue;
+        }
+        let end = rest.find('<').unwrap_or(rest.len());
+        tokens.push(Token::Text(&rest[..end]));
+        rest = &rest[end..];
+    }
+    tokens
+}
+
+fn has_class(attributes: &str, class: &str) -> bool {
+    attributes
+        .split("class=\"")
+        .skip(1)
+        .filter_map(|value| value.split('"').next())
+        .any(|classes| classes.split_whitespace().any(|c| c == class))
+}
+
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest
+            .find(';')
+            .filter(|end| *end <= 10)
+            .map(|end| (&rest[1..end], end));
+        let character = entity.and_then(|(name, _)| match name {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => name
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16).ok())
+                .unwrap_or_else(|| name.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        });
+        match (character, entity) {
+            (Some(character), Some((_, end))) => {
+                decoded.push(character);
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+pub fn html_to_readme(html: &str) -> String {
+    let mut readme = String::new();
+    let mut in_article = false;
+    let mut success = false;
+    let mut block: Option<String> = None;
+    let mut pre: Option<String> = None;
+
+    for token in tokenize(html) {
+        match token {
+            Token::Open { name, attributes } => match name.as_str() {
+                "article" if has_class(attributes, "day-desc") => in_article = true,
+                "h2" | "p" | "li" => {
+                    success = has_class(attributes, "day-success");
+                    block = Some(String::new());
+                }
+                "pre" => pre = Some(String::new()),
+                _ => (),
+            },
+            Token::Close(name) => match name.as_str() {
+                "article" => in_article = false,
+                "pre" => {
+                    if let Some(text) = pre.take().filter(|_| in_article) {
+                        readme.push_str(&text);
+                        if !text.ends_with('\n') {
+                            readme.push('\n');
+                        }
+                    }
+                }
+                "h2" | "li" | "p" => {
+                    let text = match block.take() {
+                        Some(text) => collapse_whitespace(&text),
+                        None => continue,
+                    };
+                    let answer = text.starts_with("Your puzzle answer was");
+                    if name == "p" && (in_article || answer || success) {
+                        readme.push_str(&text);
+                        readme.push_str("\n\n");
+                    } else if in_article {
+                        readme.push_str(&text);
+                        readme.push('\n');
+                    }
+                }
+                _ => (),
+            },
+            Token::Text(text) => {
+                if let Some(pre) = pre.as_mut() {
+                    pre.push_str(&decode_entities(text));
+                } else if let Some(block) = block.as_mut() {
+                    block.push_str(&decode_entities(text));
+                }
+            }
+        }
+    }
+    readme.trim_end().to_owned()
+}
+
+// Writes the README next to the solution, replacing the previous one so part
+// two shows up once part one is solved
+pub fn write_readme(day_directory: &Path, html: &str) -> io::Result<()> {
+    fs::create_dir_all(day_directory)?;
+    fs::write(day_directory.join("README.md"), html_to_readme(html))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::shared::file_parser::get_raw_input;
+
+    use super::*;
+
+    fn readme(path: &str) -> String {
+        fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join(path)).unwrap()
+    }
+
+    #[test]
+    fn test_day_1_fixture() {
+        let html = get_raw_input(file!(), "puzzle_2023_day1.html");
+        assert_eq!(readme("src/_2023/day1/README.md"), html_to_readme(&html));
+    }
+
+    #[test]
+    fn test_day_2_fixture_with_list() {
+        let html = get_raw_input(file!(), "puzzle_2023_day2.html");
+        assert_eq!(readme("src/_2023/day2/README.md"), html_to_readme(&html));
+    }
+
+    #[test]
+    fn test_entities_and_pre() {
+        let html = "<main><article class=\"day-desc\"><h2>--- Day 9: Test ---</h2>\
+            <p>Is <code>a &lt; b</code> &amp;&amp; <em>b&#62;c</em>&#x3f;</p>\
+            <pre><code>x &amp; y\n  indented\n</code></pre>\
+            <p>Done &unknown; &</p></article>\
+            <p>Answer: not part of the README</p></main>";
+        assert_eq!(
+            "--- Day 9: Test ---\nIs a < b && b>c?\n\nx & y\n  indented\nDone &unknown; &",
+            html_to_readme(html)
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use std::ops::Range;
+
+// Small xorshift64* generator, the same seed always gives the same sequence so
+// generated inputs can be reproduced from the seed alone
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // the state must never be zero, mix the seed so nearby seeds differ
+        let state = (seed ^ 0x9e37_79b9_7f4a_7c15).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        Self {
+            state: if state == 0 { 1 } else { state },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // A number in the half open range, panics on an empty range
+    pub fn range(&mut self, range: Range<usize>) -> usize {
+        assert!(!range.is_empty(), "Empty range {:?}", range);
+        range.start + (self.next_u64() % (range.end - range.start) as u64) as usize
+    }
+
+    // True with a chance of numerator / denominator
+    pub fn chance(&mut self, numerator: usize, denominator: usize) -> bool {
+        self.range(0..denominator) < numerator
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            let other = self.range(0..index + 1);
+            items.swap(index, other);
+        }
+    }
+}
+
+// Builds a random but structurally valid puzzle input, in the same text format
+// as the real inputs so it goes through the same parser. Implementations are
+// structs holding the size of the input, with a Default close to the size of
+// the personal inputs.
+pub trait InputGenerator {
+    fn generate(&self, rng: &mut Rng) -> String;
+
+    fn generate_with_seed(&self, seed: u64) -> String {
+        self.generate(&mut Rng::new(seed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        let mut c = Rng::new(8);
+        let a: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+        let b: Vec<u64> = (0..5).map(|_| b.next_u64()).collect();
+        let c: Vec<u64> = (0..5).map(|_| c.next_u64()).collect();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_range_and_shuffle() {
+        let mut rng = Rng::new(0);
+        for _ in 0..1000 {
+            let value = rng.range(3..6);
+            assert!((3..6).contains(&value));
+        }
+        let mut items: Vec<usize> = (0..10).collect();
+        rng.shuffle(&mut items);
+        let mut sorted = items.clone();
+        sorted.sort();
+        assert_eq!((0..10).collect::<Vec<usize>>(), sorted);
+    }
+}
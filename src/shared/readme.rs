@@ -0,0 +1,249 @@
+use std::{fs, str::FromStr};
+
+use super::{
+    answer::Answer,
+    file_parser::{get_day_directory, get_input},
+};
+
+// Phrases the puzzle text uses right before the answer of an example
+const EXAMPLE_ANSWER_PHRASES: [&str; 4] = ["produces", "you get", "sum is", "the sum"];
+const PUZZLE_ANSWER_PREFIX: &str = "Your puzzle answer was ";
+
+// What the README of a day says about one part of the puzzle
+#[derive(Debug, Default, PartialEq)]
+pub struct PuzzlePart {
+    pub examples: Vec<String>,
+    pub example_answer: Option<Answer>,
+    pub puzzle_answer: Option<Answer>,
+}
+
+// Example lines don't read like a sentence, prose ends with punctuation and
+// has a few words. Short introductions like "For example:" count as well.
+fn is_prose(line: &str) -> bool {
+    let words = line.matches(' ').count() + 1;
+    let capitalised = line.starts_with(|c: char| c.is_ascii_uppercase());
+    (line.ends_with(['.', '?', '!', ':']) && words >= 4)
+        || (line.ends_with(':') && capitalised && words >= 2)
+}
+
+fn is_section_title(line: &str) -> bool {
+    line.starts_with("--- ") && line.ends_with(" ---")
+}
+
+fn example_answer(sentence: &str) -> Option<Answer> {
+    if !EXAMPLE_ANSWER_PHRASES.iter().any(|p| sentence.contains(p)) {
+        return None;
+    }
+    sentence
+        .split_whitespace()
+        .rev()
+        .find_map(|word| {
+            word.trim_matches(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+                .parse::<i128>()
+                .ok()
+        })
+        .map(Answer::Number)
+}
+
+fn read_answers(part: &mut PuzzlePart, line: &str) {
+    if let Some(answer) = line.strip_prefix(PUZZLE_ANSWER_PREFIX) {
+        part.puzzle_answer = Answer::from_str(answer.trim_end_matches('.')).ok();
+        return;
+    }
+    let sentences = line.split(". ").flat_map(|s| s.split("; "));
+    if let Some(answer) = sentences.filter_map(example_answer).last() {
+        part.example_answer = Some(answer);
+    }
+}
+
+// Splits the puzzle text of a README into its parts. Examples are either
+// fenced code blocks or, as in text copied from the puzzle page, the lines
+// between a sentence ending in a colon and the next sentence.
+pub fn parse_readme(text: &str) -> Vec<PuzzlePart> {
+    let mut parts: Vec<PuzzlePart> = Vec::new();
+    let mut example: Option<Vec<&str>> = None;
+    let mut fenced = false;
+    let mut expect_example = false;
+
+    let finish_example = |parts: &mut Vec<PuzzlePart>, example: &mut Option<Vec<&str>>| {
+        if let (Some(lines), Some(part)) = (example.take(), parts.last_mut()) {
+            let block = lines.join("\n").trim_matches('\n').to_owned();
+            if !block.is_empty() {
+                part.examples.push(block);
+            }
+        }
+    };
+
+    for line in text.lines().map(|line| line.trim_end()) {
+        if line.starts_with("```") {
+            if fenced {
+                finish_example(&mut parts, &mut example);
+            } else {
+                example = Some(Vec::new());
+            }
+            fenced = !fenced;
+            continue;
+        }
+        if fenced {
+            example.get_or_insert_with(Vec::new).push(line);
+            continue;
+        }
+        if is_section_title(line) {
+            finish_example(&mut parts, &mut example);
+            parts.push(PuzzlePart::default());
+            expect_example = false;
+            continue;
+        }
+        if parts.is_empty() {
+            parts.push(PuzzlePart::default());
+        }
+        if is_prose(line) || line.starts_with(PUZZLE_ANSWER_PREFIX) {
+            finish_example(&mut parts, &mut example);
+            read_answers(parts.last_mut().unwrap(), line);
+            expect_example = line.ends_with(':');
+            continue;
+        }
+        if let Some(lines) = example.as_mut() {
+            lines.push(line);
+        } else if expect_example && !line.is_empty() {
+            example = Some(vec![line]);
+        }
+    }
+    finish_example(&mut parts, &mut example);
+    parts
+}
+
+// Examples in the order they first appear, puzzles often repeat the example
+// of part one in part two
+pub fn distinct_examples(parts: &[PuzzlePart]) -> Vec<&str> {
+    let mut examples: Vec<&str> = Vec::new();
+    for example in parts.iter().flat_map(|part| part.examples.iter()) {
+        if !examples.contains(&example.as_str()) {
+            examples.push(example);
+        }
+    }
+    examples
+}
+
+// The README next to the solution file
+pub fn get_readme_parts(file_path: &str) -> Vec<PuzzlePart> {
+    let readme = get_day_directory(file_path).join("README.md");
+    parse_readme(&fs::read_to_string(readme).unwrap())
+}
+
+// Checks that the distinct README examples, in order, match the given example
+// assets. With AOC_WRITE_EXAMPLES=1 the assets are (re)written from the README
+// instead, so new examples only have to be listed here.
+pub fn verify_example_assets(file_path: &str, assets: &[&str]) -> Result<(), String> {
+    let parts = get_readme_parts(file_path);
+    let examples = distinct_examples(&parts);
+    if examples.len() != assets.len() {
+        return Err(format!(
+            "README has {} distinct examples but {} assets are listed",
+            examples.len(),
+            assets.len()
+        ));
+    }
+    let directory = get_day_directory(file_path).join("assets");
+    let write = std::env::var("AOC_WRITE_EXAMPLES").is_ok_and(|value| value != "0");
+    for (example, asset) in examples.iter().zip(assets) {
+        let path = directory.join(asset);
+        if write {
+            fs::write(&path, example).map_err(|e| format!("{}: {}", path.display(), e))?;
+            continue;
+        }
+        let content =
+            fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let content = super::file_parser::normalize_line_endings(&content);
+        if super::file_parser::trim_trailing_newlines(&content) != *example {
+            return Err(format!(
+                "{} does not match the README example:\n{}",
+                path.display(),
+                example
+            ));
+        }
+    }
+    Ok(())
+}
+
+// The readme test of a day: the example assets match the README, and `solve`
+// (given the part, from 1, and an input) gives the answers the README states
+// for the first example of every part and for the puzzle input
+pub fn check_readme_examples<T: Into<Answer>>(
+    file_path: &str,
+    assets: &[&str],
+    input: &str,
+    solve: impl Fn(u8, String) -> T,
+) {
+    verify_example_assets(file_path, assets).unwrap();
+    let input = get_input(file_path, input);
+    for (part, readme) in (1..).zip(get_readme_parts(file_path)) {
+        let example = solve(part, readme.examples[0].clone());
+        assert_eq!(readme.example_answer, Some(example.into()), "part {}", part);
+        let solution = solve(part, input.clone());
+        assert_eq!(readme.puzzle_answer, Some(solution.into()), "part {}", part);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const README: &str = "--- Day 1: Test ---
+Some story about the elves.
+
+For example:
+
+1abc2
+a1b2c3d4e5f
+In this example, the values of these lines are 12 and 15. Adding these together produces 27.
+
+Your puzzle answer was 55029.
+
+--- Part Two ---
+Again consider the example from earlier:
+
+1abc2
+a1b2c3d4e5f
+The numbers are 2 and 4; their sum is 6.
+
+```
+one
+two
+```
+
+Your puzzle answer was ABC.
+";
+
+    #[test]
+    fn test_parse_readme() {
+        let parts = parse_readme(README);
+        assert_eq!(2, parts.len());
+        assert_eq!(vec!["1abc2\na1b2c3d4e5f"], parts[0].examples);
+        assert_eq!(Some(Answer::Number(27)), parts[0].example_answer);
+        assert_eq!(Some(Answer::Number(55029)), parts[0].puzzle_answer);
+        assert_eq!(vec!["1abc2\na1b2c3d4e5f", "one\ntwo"], parts[1].examples);
+        assert_eq!(Some(Answer::Number(6)), parts[1].example_answer);
+        assert_eq!(Some(Answer::from("ABC")), parts[1].puzzle_answer);
+        assert_eq!(
+            vec!["1abc2\na1b2c3d4e5f", "one\ntwo"],
+            distinct_examples(&parts)
+        );
+    }
+
+    #[test]
+    fn test_prose_and_examples() {
+        assert!(is_prose("In this example, there are two gears."));
+        assert!(is_prose("For example:"));
+        assert!(!is_prose("467..114.."));
+        assert!(!is_prose("seed-to-soil map:"));
+        assert!(!is_prose(
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"
+        ));
+        assert_eq!(
+            Some(Answer::Number(2286)),
+            example_answer("Adding up these five powers produces the sum 2286")
+        );
+        assert_eq!(None, example_answer("The power of the set in game 1 is 48"));
+    }
+}
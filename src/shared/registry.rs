@@ -0,0 +1,155 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use super::{
+    allocation::{measure, AllocationStats},
+    answer::Answer,
+    cancellation::with_time_budget,
+};
+
+type Solver = Box<dyn Fn() -> Answer + Send + Sync>;
+
+// A part of a day the runner knows how to solve on the real input. Days list
+// their parts next to the solution, the same way their tests call it.
+pub struct DayPart {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub expected: Option<Answer>,
+    pub solve: Solver,
+}
+
+impl DayPart {
+    pub fn new(
+        year: u16,
+        day: u8,
+        part: u8,
+        solve: impl Fn() -> Answer + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            year,
+            day,
+            part,
+            expected: None,
+            solve: Box::new(solve),
+        }
+    }
+
+    pub fn expecting(mut self, answer: impl Into<Answer>) -> Self {
+        self.expected = Some(answer.into());
+        self
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    Correct,
+    // no known answer to compare with
+    Unknown,
+    Wrong,
+    Panicked(String),
+    TimedOut(Duration),
+}
+
+impl Status {
+    pub fn is_regression(&self) -> bool {
+        matches!(
+            self,
+            Status::Wrong | Status::Panicked(_) | Status::TimedOut(_)
+        )
+    }
+}
+
+pub struct PartResult<'a> {
+    pub part: &'a DayPart,
+    pub answer: Answer,
+    pub duration: Duration,
+    pub status: Status,
+    // only with the alloc-stats feature
+    pub allocations: Option<AllocationStats>,
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| String::from("panicked"))
+}
+
+pub fn run_part(part: &DayPart, budget: Option<Duration>) -> PartResult<'_> {
+    let start = Instant::now();
+    let (result, allocations) =
+        measure(|| panic::catch_unwind(AssertUnwindSafe(|| with_time_budget(budget, &part.solve))));
+    let duration = start.elapsed();
+    let (answer, status) = match result {
+        Ok(Err(timed_out)) => (Answer::Unsolved, Status::TimedOut(timed_out.budget)),
+        Ok(Ok(answer)) => {
+            let status = match &part.expected {
+                Some(expected) if *expected == answer => Status::Correct,
+                Some(_) => Status::Wrong,
+                None => Status::Unknown,
+            };
+            (answer, status)
+        }
+        Err(payload) => (Answer::Unsolved, Status::Panicked(panic_message(&*payload))),
+    };
+    PartResult {
+        part,
+        answer,
+        duration,
+        status,
+        allocations,
+    }
+}
+
+// Runs the parts on the current rayon pool, each within its own time budget.
+// Results are in the order of the parts whatever order they finish in.
+pub fn run_parts(parts: &[DayPart], budget: Option<Duration>) -> Vec<PartResult<'_>> {
+    parts
+        .par_iter()
+        .map(|part| run_part(part, budget))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shared::cancellation::checkpoint;
+
+    use super::*;
+
+    #[test]
+    fn test_run_parts() {
+        let parts = vec![
+            DayPart::new(2023, 1, 1, || Answer::from(42)).expecting(42),
+            DayPart::new(2023, 1, 2, || Answer::from(41)).expecting(42),
+            DayPart::new(2023, 2, 1, || Answer::from("new")),
+            DayPart::new(2023, 2, 2, || panic!("no input")).expecting(1),
+            DayPart::new(2023, 3, 1, || loop {
+                checkpoint();
+            })
+            .expecting(1),
+        ];
+        let results = run_parts(&parts, Some(Duration::from_millis(20)));
+        let statuses: Vec<&Status> = results.iter().map(|result| &result.status).collect();
+        assert_eq!(
+            vec![
+                &Status::Correct,
+                &Status::Wrong,
+                &Status::Unknown,
+                &Status::Panicked(String::from("no input")),
+                &Status::TimedOut(Duration::from_millis(20))
+            ],
+            statuses
+        );
+        assert_eq!(
+            3,
+            results.iter().filter(|r| r.status.is_regression()).count()
+        );
+        assert_eq!(Answer::from(41), results[1].answer);
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    env, fmt, fs, io,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use super::puzzle_page::write_readme;
+
+const BASE_URL: &str = "https://adventofcode.com";
+const USER_AGENT: &str = "adventofcode-rust-solutions (curl)";
+
+#[derive(Debug)]
+pub enum ClientError {
+    MissingSession,
+    InvalidValue(&'static str),
+    Io(io::Error),
+    Request { url: String, message: String },
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::MissingSession => {
+                write!(
+                    f,
+                    "Set AOC_SESSION to the session cookie of adventofcode.com"
+                )
+            }
+            ClientError::InvalidValue(name) => {
+                write!(f, "{} can't contain control characters", name)
+            }
+            ClientError::Io(err) => write!(f, "{}", err),
+            ClientError::Request { url, message } => write!(f, "{} failed: {}", url, message),
+        }
+    }
+}
+
+impl From<io::Error> for ClientError {
+    fn from(err: io::Error) -> Self {
+        ClientError::Io(err)
+    }
+}
+
+// Directory of a day's solution, e.g. src/_2023/day1
+pub fn day_directory(year: u16, day: u8) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join(format!("_{}", year))
+        .join(format!("day{}", day))
+}
+
+// A value for a quoted string of a curl config file. Quotes and backslashes are
+// escaped, a newline would end up in the request headers so it's refused.
+fn config_string(name: &'static str, value: &str) -> Result<String, ClientError> {
+    if value.chars().any(char::is_control) {
+        return Err(ClientError::InvalidValue(name));
+    }
+    Ok(value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Every request to adventofcode.com goes through this client so the session
+// cookie and user agent are handled in one place. Requests are made with curl,
+// the cookie is passed on stdin so it doesn't show up in the process list.
+pub struct SessionClient {
+    session: String,
+    base_url: String,
+}
+
+impl SessionClient {
+    pub fn new(session: &str) -> Self {
+        Self {
+            session: session.trim().to_owned(),
+            base_url: BASE_URL.to_owned(),
+        }
+    }
+
+    // AOC_BASE_URL points the client at another server, e.g. a local mirror
+    pub fn from_env() -> Result<Self, ClientError> {
+        let client = match env::var("AOC_SESSION") {
+            Ok(session) if !session.trim().is_empty() => Self::new(&session),
+            _ => return Err(ClientError::MissingSession),
+        };
+        Ok(match env::var("AOC_BASE_URL") {
+            Ok(base_url) if !base_url.trim().is_empty() => client.with_base_url(base_url.trim()),
+            _ => client,
+        })
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_owned();
+        self
+    }
+
+    fn curl_config(&self, url: &str) -> Result<String, ClientError> {
+        let user_agent = env::var("AOC_USER_AGENT").unwrap_or_else(|_| USER_AGENT.to_owned());
+        Ok(format!(
+            "url = \"{}\"\ncookie = \"session={}\"\nuser-agent = \"{}\"\n",
+            config_string("url", url)?,
+            config_string("AOC_SESSION", &self.session)?,
+            config_string("AOC_USER_AGENT", &user_agent)?
+        ))
+    }
+
+    pub fn get(&self, path: &str) -> Result<String, ClientError> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut curl = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--config", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        curl.stdin
+            .take()
+            .unwrap()
+            .write_all(self.curl_config(&url)?.as_bytes())?;
+        let output = curl.wait_with_output()?;
+        if !output.status.success() {
+            return Err(ClientError::Request {
+                url,
+                message: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            });
+        }
+        String::from_utf8(output.stdout).map_err(|err| ClientError::Request {
+            url,
+            message: err.to_string(),
+        })
+    }
+
+    pub fn input(&self, year: u16, day: u8) -> Result<String, ClientError> {
+        self.get(&format!("/{}/day/{}/input", year, day))
+    }
+
+    pub fn puzzle_page(&self, year: u16, day: u8) -> Result<String, ClientError> {
+        self.get(&format!("/{}/day/{}", year, day))
+    }
+
+    // Inputs never change, an existing file is left alone
+    pub fn download_input(
+        &self,
+        year: u16,
+        day: u8,
+        file_name: &str,
+    ) -> Result<PathBuf, ClientError> {
+        let assets = day_directory(year, day).join("assets");
+        let path = assets.join(file_name);
+        if !path.exists() {
+            let input = self.input(year, day)?;
+            fs::create_dir_all(&assets)?;
+            fs::write(&path, input)?;
+        }
+        Ok(path)
+    }
+
+    pub fn download_readme(&self, year: u16, day: u8) -> Result<PathBuf, ClientError> {
+        let directory = day_directory(year, day);
+        write_readme(&directory, &self.puzzle_page(year, day)?)?;
+        Ok(directory.join("README.md"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_directory() {
+        assert!(day_directory(2023, 1).ends_with("src/_2023/day1"));
+        assert!(day_directory(2023, 1).join("day_one.rs").exists());
+    }
+
+    #[test]
+    fn test_curl_config() {
+        let client = SessionClient::new(" 53616c74\n").with_base_url("http://localhost:8080/");
+        assert_eq!("http://localhost:8080", client.base_url);
+        let config = client
+            .curl_config("http://localhost:8080/2023/day/1")
+            .unwrap();
+        assert!(config.starts_with(
+            "url = \"http://localhost:8080/2023/day/1\"\ncookie = \"session=53616c74\"\n"
+        ));
+    }
+
+    #[test]
+    fn test_curl_config_escaping() {
+        let client = SessionClient::new("a\"b\\c");
+        let config = client.curl_config("http://localhost/").unwrap();
+        assert!(config.contains("cookie = \"session=a\\\"b\\\\c\"\n"));
+        let client = SessionClient::new("abc\nuser-agent = \"evil\"");
+        assert!(matches!(
+            client.curl_config("http://localhost/"),
+            Err(ClientError::InvalidValue("AOC_SESSION"))
+        ));
+    }
+
+    #[test]
+    #[ignore = "downloads from adventofcode.com, needs AOC_SESSION"]
+    fn download_readme_2023_day_1() {
+        let client = SessionClient::from_env().unwrap();
+        client.download_readme(2023, 1).unwrap();
+    }
+}
@@ -0,0 +1,195 @@
+use std::{fmt, str::FromStr, sync::RwLock};
+
+// Diagnostics for solvers, silent unless enabled through the AOC_TRACE
+// environment variable or set_filter (e.g. from a --trace runner flag).
+//
+// The filter is a comma separated list of `level` (applies to every day) and
+// `target=level` entries, targets being prefixes like `2023` or `2023::day14`:
+//
+//   AOC_TRACE=debug
+//   AOC_TRACE=warn,2023::day14=trace,2023::day17=off
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            other => Err(format!("Unknown trace level {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Default, Debug, PartialEq)]
+pub struct Filter {
+    default: Option<Level>,
+    // None switches a target off
+    targets: Vec<(String, Option<Level>)>,
+}
+
+fn parse_max_level(s: &str) -> Result<Option<Level>, String> {
+    match s.trim() {
+        "off" => Ok(None),
+        level => level.parse().map(Some),
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filter = Filter::default();
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => filter
+                    .targets
+                    .push((target.trim().to_owned(), parse_max_level(level)?)),
+                None => filter.default = parse_max_level(directive)?,
+            }
+        }
+        Ok(filter)
+    }
+}
+
+impl Filter {
+    pub fn enabled(&self, level: Level, target: &str) -> bool {
+        // the most specific matching target wins
+        let max_level = self
+            .targets
+            .iter()
+            .filter(|(prefix, _)| target == prefix || target.starts_with(&format!("{}::", prefix)))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default);
+        match max_level {
+            Some(max_level) => level <= max_level,
+            None => false,
+        }
+    }
+}
+
+static FILTER: RwLock<Option<Filter>> = RwLock::new(None);
+
+pub fn set_filter(spec: &str) -> Result<(), String> {
+    let filter = spec.parse()?;
+    *FILTER.write().unwrap() = Some(filter);
+    Ok(())
+}
+
+fn filter_from_env() -> Filter {
+    match std::env::var("AOC_TRACE") {
+        Ok(spec) => spec.parse().unwrap_or_else(|err| {
+            eprintln!("Ignoring AOC_TRACE: {}", err);
+            Filter::default()
+        }),
+        Err(_) => Filter::default(),
+    }
+}
+
+// Turns a module path (`adventofcode::_2023::day14::day_fourteen`) or a file
+// path (`src/_2023/day14/day_fourteen.rs`) into a target like `2023::day14`
+pub fn target(location: &str) -> String {
+    let parts: Vec<&str> = location.split(['/', ':']).collect();
+    let numbered = |prefix: &str| {
+        parts.iter().find_map(|part| {
+            part.strip_prefix(prefix)
+                .filter(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+                .map(|_| *part)
+        })
+    };
+    match (numbered("_"), numbered("day")) {
+        (Some(year), Some(day)) => format!("{}::{}", &year[1..], day),
+        (Some(year), None) => year[1..].to_owned(),
+        _ => location.trim_start_matches("adventofcode::").to_owned(),
+    }
+}
+
+pub fn enabled(level: Level, location: &str) -> bool {
+    if FILTER.read().unwrap().is_none() {
+        let mut filter = FILTER.write().unwrap();
+        if filter.is_none() {
+            *filter = Some(filter_from_env());
+        }
+    }
+    FILTER
+        .read()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|filter| filter.enabled(level, &target(location)))
+}
+
+pub fn log(level: Level, location: &str, args: fmt::Arguments) {
+    eprintln!("[{} {}] {}", level, target(location), args);
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($level:ident, $($arg:tt)+) => {
+        if $crate::shared::trace::enabled($crate::shared::trace::Level::$level, module_path!()) {
+            $crate::shared::trace::log(
+                $crate::shared::trace::Level::$level,
+                module_path!(),
+                format_args!($($arg)+),
+            );
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target() {
+        assert_eq!(
+            "2023::day14",
+            target("adventofcode::_2023::day14::day_fourteen")
+        );
+        assert_eq!("2023::day14", target("src/_2023/day14/day_fourteen.rs"));
+        assert_eq!("2024::day6", target("adventofcode::_2024::day6::solution"));
+        assert_eq!("2024::day1", target("adventofcode::_2024::day1::day1"));
+        assert_eq!("shared::trace", target("adventofcode::shared::trace"));
+    }
+
+    #[test]
+    fn test_filter() {
+        let filter: Filter = "warn,2023::day14=trace,2024=off".parse().unwrap();
+        assert!(filter.enabled(Level::Trace, "2023::day14"));
+        assert!(!filter.enabled(Level::Trace, "2023::day1"));
+        assert!(filter.enabled(Level::Warn, "2023::day1"));
+        assert!(!filter.enabled(Level::Error, "2024::day6"));
+        assert!("2023=loud".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn test_silent_by_default() {
+        let filter = Filter::default();
+        assert!(!filter.enabled(Level::Error, "2023::day14"));
+    }
+}